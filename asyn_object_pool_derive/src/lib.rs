@@ -0,0 +1,361 @@
+//! `#[derive(Resettable)]` for [`asyn_object_pool`](https://docs.rs/asyn_object_pool).
+//!
+//! The macro is re-exported by `asyn_object_pool` behind its `derive` feature (enabled by default); depend on that
+//! crate rather than on this one.
+
+use proc_macro::TokenStream;
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, Data, DeriveInput, Expr, Fields, Generics, Ident, Path, Type, parse_macro_input,
+    parse_quote,
+};
+
+/// Derives `Resettable` by resetting every field in turn.
+///
+/// A field is reset with its own `Resettable` impl unless it carries one of these attributes:
+///
+/// * `#[reset(skip)]` leaves the field untouched, e.g. for an id that identifies the pooled object;
+/// * `#[reset(default)]` assigns `Default::default()`;
+/// * `#[reset(value = expr)]` assigns `expr`;
+/// * `#[reset(with = path)]` calls `path(&mut field)`, e.g. `#[reset(with = Vec::clear)]`.
+///
+/// The generated `try_reset` resets the fields the same way and asks for the object to be discarded if any field
+/// reset with its own `Resettable` impl does.
+///
+/// On an enum, exactly one variant must be marked `#[reset(default)]`: that is the variant the value is reset to. If
+/// the value already is that variant, its fields are reset in place as above, which keeps their allocations.
+/// Otherwise it is replaced by the marked variant, whose fields are built with their `value = expr` if they have one
+/// and with `Default::default()` if not. Every other field of that variant must therefore implement `Default`, even
+/// one marked `#[reset(skip)]` or `#[reset(with = path)]`: skipping only applies to the in-place reset.
+///
+/// The macro rejects unions, enums without exactly one `#[reset(default)]` variant, fields with more than one option
+/// and `#[reset(...)]` on the fields of any other variant.
+///
+/// # Examples
+///
+/// ```
+/// use asyn_object_pool::Resettable;
+///
+/// #[derive(Resettable)]
+/// struct Session {
+///     #[reset(skip)]
+///     id: u32,
+///     #[reset(with = Vec::clear)]
+///     buffer: Vec<u8>,
+///     #[reset(value = true)]
+///     connected: bool,
+///     #[reset(default)]
+///     user: Option<String>,
+///     cache: Option<Cache>,
+/// }
+///
+/// struct Cache { hits: u32 }
+///
+/// impl Resettable for Cache {
+///     fn reset(&mut self) { self.hits = 0; }
+/// }
+///
+/// let mut session = Session {
+///     id: 7,
+///     buffer: vec![1, 2, 3],
+///     connected: false,
+///     user: Some("alice".to_string()),
+///     cache: Some(Cache { hits: 3 }),
+/// };
+/// session.reset();
+///
+/// assert_eq!(session.id, 7);
+/// assert!(session.buffer.is_empty());
+/// assert!(session.connected);
+/// assert_eq!(session.user, None);
+/// assert_eq!(session.cache.unwrap().hits, 0);
+/// ```
+///
+/// Enums are reset to their `#[reset(default)]` variant:
+///
+/// ```
+/// use asyn_object_pool::Resettable;
+///
+/// #[derive(Debug, PartialEq, Resettable)]
+/// enum Message {
+///     Sensor { id: u32, value: f64 },
+///     #[reset(default)]
+///     Heartbeat,
+/// }
+///
+/// let mut message = Message::Sensor { id: 1, value: 0.5 };
+/// message.reset();
+/// assert_eq!(message, Message::Heartbeat);
+/// ```
+#[proc_macro_derive(Resettable, attributes(reset))]
+pub fn derive_resettable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+// what to do with a field on reset.
+enum Action {
+    Reset,
+    Skip,
+    Default,
+    Value(Expr),
+    With(Path),
+}
+
+impl Action {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut action = None;
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("reset")) {
+            attr.parse_nested_meta(|meta| {
+                let next = if meta.path.is_ident("skip") {
+                    Action::Skip
+                } else if meta.path.is_ident("default") {
+                    Action::Default
+                } else if meta.path.is_ident("value") {
+                    Action::Value(meta.value()?.parse()?)
+                } else if meta.path.is_ident("with") {
+                    Action::With(meta.value()?.parse()?)
+                } else {
+                    return Err(
+                        meta.error("expected `skip`, `default`, `value = ...` or `with = ...`")
+                    );
+                };
+                if action.replace(next).is_some() {
+                    return Err(meta.error("a field takes a single #[reset(...)] option"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(action.unwrap_or(Action::Reset))
+    }
+
+    // resets the field at `place`, an expression that can be assigned to and borrowed mutably.
+    fn apply(&self, place: &TokenStream2) -> TokenStream2 {
+        self.apply_with(
+            place,
+            quote!(::asyn_object_pool::Resettable::reset(&mut #place);),
+        )
+    }
+
+    // like `apply`, but folds the outcome of a `try_reset` into the local `outcome`.
+    fn try_apply(&self, place: &TokenStream2) -> TokenStream2 {
+        self.apply_with(
+            place,
+            quote!(outcome = outcome.and(::asyn_object_pool::Resettable::try_reset(&mut #place));),
+        )
+    }
+
+    fn apply_with(&self, place: &TokenStream2, reset: TokenStream2) -> TokenStream2 {
+        match self {
+            Action::Reset => reset,
+            Action::Skip => quote!(),
+            Action::Default => quote!(#place = ::core::default::Default::default();),
+            Action::Value(expr) => quote!(#place = #expr;),
+            Action::With(path) => quote!(#path(&mut #place);),
+        }
+    }
+
+    // the value of the field when a variant is built from scratch.
+    fn init(&self) -> TokenStream2 {
+        match self {
+            Action::Value(expr) => quote!(#expr),
+            _ => quote!(::core::default::Default::default()),
+        }
+    }
+}
+
+struct Field {
+    // `name` for a named field, the index for a tuple field.
+    member: syn::Member,
+    ty: Type,
+    action: Action,
+}
+
+fn fields(fields: &Fields) -> syn::Result<Vec<Field>> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            Ok(Field {
+                member: match &field.ident {
+                    Some(ident) => syn::Member::Named(ident.clone()),
+                    None => syn::Member::Unnamed(i.into()),
+                },
+                ty: field.ty.clone(),
+                action: Action::parse(&field.attrs)?,
+            })
+        })
+        .collect()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let mut generics = input.generics.clone();
+    let (reset, try_reset) = match &input.data {
+        Data::Struct(data) => {
+            let fields = fields(&data.fields)?;
+            add_bounds(&mut generics, &input.generics, &fields, false);
+            let places: Vec<TokenStream2> = fields
+                .iter()
+                .map(|field| {
+                    let member = &field.member;
+                    quote!(self.#member)
+                })
+                .collect();
+            let resets = fields
+                .iter()
+                .zip(&places)
+                .map(|(field, place)| field.action.apply(place));
+            let try_resets = fields
+                .iter()
+                .zip(&places)
+                .map(|(field, place)| field.action.try_apply(place));
+            (quote!(#(#resets)*), quote!(#(#try_resets)*))
+        }
+        Data::Enum(data) => {
+            let mut target = None;
+            for variant in &data.variants {
+                if is_reset_target(&variant.attrs)? && target.replace(variant).is_some() {
+                    return Err(syn::Error::new_spanned(
+                        &variant.ident,
+                        "only one variant can be marked #[reset(default)]",
+                    ));
+                }
+            }
+            let Some(target) = target else {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "#[derive(Resettable)] on an enum needs a variant marked #[reset(default)]",
+                ));
+            };
+            for variant in &data.variants {
+                for field in &variant.fields {
+                    if field.attrs.iter().any(|attr| attr.path().is_ident("reset"))
+                        && variant.ident != target.ident
+                    {
+                        return Err(syn::Error::new_spanned(
+                            field,
+                            "#[reset(...)] only applies to fields of the #[reset(default)] variant",
+                        ));
+                    }
+                }
+            }
+
+            let ident = &target.ident;
+            let fields = fields(&target.fields)?;
+            add_bounds(&mut generics, &input.generics, &fields, true);
+            let bindings: Vec<Ident> = (0..fields.len())
+                .map(|i| format_ident!("__field{}", i))
+                .collect();
+            let members: Vec<&syn::Member> = fields.iter().map(|field| &field.member).collect();
+            let resets = fields
+                .iter()
+                .zip(&bindings)
+                .map(|(field, binding)| field.action.apply(&quote!(*#binding)));
+            let try_resets = fields
+                .iter()
+                .zip(&bindings)
+                .map(|(field, binding)| field.action.try_apply(&quote!(*#binding)));
+            let inits: Vec<TokenStream2> = fields
+                .iter()
+                .map(|field| {
+                    let member = &field.member;
+                    let init = field.action.init();
+                    quote!(#member: #init)
+                })
+                .collect();
+            (
+                quote! {
+                    match self {
+                        Self::#ident { #(#members: #bindings),* } => { #(#resets)* }
+                        #[allow(unreachable_patterns)]
+                        _ => *self = Self::#ident { #(#inits),* },
+                    }
+                },
+                quote! {
+                    match self {
+                        Self::#ident { #(#members: #bindings),* } => { #(#try_resets)* }
+                        #[allow(unreachable_patterns)]
+                        _ => *self = Self::#ident { #(#inits),* },
+                    }
+                },
+            )
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new_spanned(
+                data.union_token,
+                "#[derive(Resettable)] does not support unions",
+            ));
+        }
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::asyn_object_pool::Resettable for #name #ty_generics #where_clause {
+            fn reset(&mut self) {
+                #reset
+            }
+
+            fn try_reset(&mut self) -> ::asyn_object_pool::ResetOutcome {
+                #[allow(unused_mut)]
+                let mut outcome = ::asyn_object_pool::ResetOutcome::Keep;
+                #try_reset
+                outcome
+            }
+        }
+    })
+}
+
+fn is_reset_target(attrs: &[Attribute]) -> syn::Result<bool> {
+    let mut target = false;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("reset")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("default") {
+                target = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `default` on a variant"))
+            }
+        })?;
+    }
+    Ok(target)
+}
+
+// bounds the types of generic fields by what their reset needs; `built` is set for the fields of an enum variant,
+// which may also be built with `Default::default()`.
+fn add_bounds(generics: &mut Generics, original: &Generics, fields: &[Field], built: bool) {
+    let params: Vec<&Ident> = original.type_params().map(|param| &param.ident).collect();
+    if params.is_empty() {
+        return;
+    }
+    let where_clause = generics.make_where_clause();
+    for field in fields {
+        let ty = &field.ty;
+        if !mentions(quote!(#ty), &params) {
+            continue;
+        }
+        if let Action::Reset = field.action {
+            where_clause
+                .predicates
+                .push(parse_quote!(#ty: ::asyn_object_pool::Resettable));
+        }
+        if matches!(field.action, Action::Default)
+            || (built && !matches!(field.action, Action::Value(_)))
+        {
+            where_clause
+                .predicates
+                .push(parse_quote!(#ty: ::core::default::Default));
+        }
+    }
+}
+
+fn mentions(tokens: TokenStream2, params: &[&Ident]) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => params.contains(&&ident),
+        TokenTree::Group(group) => mentions(group.stream(), params),
+        _ => false,
+    })
+}
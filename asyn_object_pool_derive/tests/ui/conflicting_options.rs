@@ -0,0 +1,9 @@
+use asyn_object_pool::Resettable;
+
+#[derive(Resettable)]
+struct Session {
+    #[reset(skip, default)]
+    id: u32,
+}
+
+fn main() {}
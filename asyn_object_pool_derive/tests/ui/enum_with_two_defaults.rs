@@ -0,0 +1,11 @@
+use asyn_object_pool::Resettable;
+
+#[derive(Resettable)]
+enum Message {
+    #[reset(default)]
+    Sensor { id: u32 },
+    #[reset(default)]
+    Heartbeat,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use asyn_object_pool::Resettable;
+
+#[derive(Resettable)]
+enum Message {
+    Sensor { id: u32 },
+    Heartbeat,
+}
+
+fn main() {}
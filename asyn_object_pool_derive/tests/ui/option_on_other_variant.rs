@@ -0,0 +1,13 @@
+use asyn_object_pool::Resettable;
+
+#[derive(Resettable)]
+enum Message {
+    Sensor {
+        #[reset(skip)]
+        id: u32,
+    },
+    #[reset(default)]
+    Heartbeat,
+}
+
+fn main() {}
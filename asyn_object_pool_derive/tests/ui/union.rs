@@ -0,0 +1,9 @@
+use asyn_object_pool::Resettable;
+
+#[derive(Resettable)]
+union Bits {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
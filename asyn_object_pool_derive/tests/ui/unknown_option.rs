@@ -0,0 +1,9 @@
+use asyn_object_pool::Resettable;
+
+#[derive(Resettable)]
+struct Session {
+    #[reset(clear)]
+    buffer: Vec<u8>,
+}
+
+fn main() {}
@@ -33,20 +33,21 @@ use tokio::{sync::Notify, time::sleep};
 
 // ── Message ───────────────────────────────────────────────────────────────────
 
-#[derive(Debug)]
+#[derive(Debug, Resettable)]
 #[allow(dead_code)]
 enum Message {
-    Sensor { id: u32, value: f64 },
-    Processed { source_id: u32, result: f64 },
+    Sensor {
+        id: u32,
+        value: f64,
+    },
+    Processed {
+        source_id: u32,
+        result: f64,
+    },
+    #[reset(default)]
     Heartbeat,
 }
 
-impl Resettable for Message {
-    fn reset(&mut self) {
-        *self = Message::Heartbeat;
-    }
-}
-
 type Shared = Arc<BundledPoolItem<Message>>;
 
 // ── Publish result ────────────────────────────────────────────────────────────
@@ -302,7 +303,7 @@ impl<P: Processor> Runner<P> {
         thread::spawn(move || {
             while !self.stop.load(Ordering::Relaxed) || !self.inbox.is_empty() {
                 if let Some(input) = self.inbox.recv() {
-                    let mut output = self.pool.take();
+                    let mut output = self.pool.take().expect("sync factory never fails");
                     self.processor.process(&input, &mut output);
                     if let Err(e) = self.outbus.publish(output.into_arc()) {
                         eprintln!("[runner] publish error: {e}");
@@ -395,7 +396,7 @@ async fn main() {
 
     // Produce messages one per millisecond so consumers visibly interleave
     for i in 0..10u32 {
-        let mut msg = pool.take();
+        let mut msg = pool.take().expect("sync factory never fails");
         *msg = Message::Sensor {
             id: i,
             value: i as f64,
@@ -1,5 +1,4 @@
-use asyn_object_pool::{BundledPool, Resettable};
-use rand;
+use asyn_object_pool::{BundledPool, CapacityCapped, PoolError, Resettable};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use thiserror::Error;
@@ -12,6 +11,12 @@ pub enum ExampleError {
     Error(String, String),
 }
 
+impl<E: std::fmt::Display> From<PoolError<E>> for ExampleError {
+    fn from(e: PoolError<E>) -> Self {
+        ExampleError::Error("Pool".to_string(), e.to_string())
+    }
+}
+
 // Example 1: Database Connection Pool Simulation
 #[derive(Debug)]
 struct DatabaseConnection {
@@ -30,6 +35,13 @@ impl DatabaseConnection {
         }
     }
 
+    /// Opens a connection asynchronously, as a real driver would.
+    async fn connect(id: u32) -> Result<Self, ExampleError> {
+        // Simulate the connection handshake
+        sleep(Duration::from_millis(5)).await;
+        Ok(Self::new(id))
+    }
+
     async fn execute_query(&mut self, query: &str) -> Result<String, ExampleError> {
         if !self.connected {
             return Err(ExampleError::Error(
@@ -43,7 +55,7 @@ impl DatabaseConnection {
         self.query_count += 1;
 
         // Simulate occasional query failures
-        if self.query_count % 7 == 0 {
+        if self.query_count.is_multiple_of(7) {
             return Err(ExampleError::Error(
                 "Database".to_string(),
                 format!("Query execution failed: {}", query),
@@ -72,6 +84,8 @@ struct HttpClient {
     base_url: String,
     request_count: u32,
     timeout: Duration,
+    // Cleared when the connection behind the client times out
+    healthy: bool,
 }
 
 impl HttpClient {
@@ -81,6 +95,7 @@ impl HttpClient {
             base_url,
             request_count: 0,
             timeout: Duration::from_secs(30),
+            healthy: true,
         }
     }
 
@@ -90,14 +105,15 @@ impl HttpClient {
         self.request_count += 1;
 
         // Simulate occasional HTTP errors
-        if self.request_count % 5 == 0 {
+        if self.request_count.is_multiple_of(5) {
             return Err(ExampleError::Error(
                 "HTTP".to_string(),
-                format!("Request failed with status: 500"),
+                "Request failed with status: 500".to_string(),
             ));
         }
 
         if path.contains("timeout") {
+            self.healthy = false;
             return Err(ExampleError::Error(
                 "HTTP".to_string(),
                 "Network timeout".to_string(),
@@ -120,17 +136,19 @@ impl Resettable for HttpClient {
 }
 
 // Example 3: Buffer Pool for processing data
-#[derive(Debug)]
+#[derive(Debug, Resettable)]
 struct ProcessingBuffer {
-    data: Vec<u8>,
+    // Cleared on reset, and shrunk back if a large batch grew it past 4 KiB
+    data: CapacityCapped<Vec<u8>, 4096>,
     processed_items: usize,
+    #[reset(skip)]
     capacity: usize,
 }
 
 impl ProcessingBuffer {
     fn new() -> Self {
         Self {
-            data: Vec::with_capacity(1024),
+            data: CapacityCapped::new(Vec::with_capacity(1024)),
             processed_items: 0,
             capacity: 1024,
         }
@@ -158,28 +176,24 @@ impl ProcessingBuffer {
     }
 }
 
-impl Resettable for ProcessingBuffer {
-    fn reset(&mut self) {
-        self.data.clear();
-        self.processed_items = 0;
-    }
-}
-
 async fn database_pool_example() -> Result<(), ExampleError> {
     use std::sync::atomic::{AtomicU32, Ordering};
 
     let connection_id = Arc::new(AtomicU32::new(0));
-    let db_pool = Arc::new(BundledPool::new(
-        2, // initial capacity
-        5, // maximum capacity
-        {
-            let connection_id = Arc::clone(&connection_id);
-            move || {
-                let id = connection_id.fetch_add(1, Ordering::SeqCst) + 1;
-                DatabaseConnection::new(id)
-            }
-        },
-    ));
+    let db_pool = Arc::new(
+        BundledPool::new_async(
+            2, // initial capacity
+            5, // maximum capacity
+            {
+                let connection_id = Arc::clone(&connection_id);
+                move || {
+                    let id = connection_id.fetch_add(1, Ordering::SeqCst) + 1;
+                    DatabaseConnection::connect(id)
+                }
+            },
+        )
+        .await?,
+    );
 
     println!(
         "Created database pool with {} available connections",
@@ -192,7 +206,7 @@ async fn database_pool_example() -> Result<(), ExampleError> {
     for i in 0..10 {
         let pool = Arc::clone(&db_pool);
         let handle = tokio::spawn(async move {
-            let mut conn = pool.take();
+            let mut conn = pool.take_async().await?;
             let query = format!("SELECT * FROM users WHERE id = {}", i);
 
             match conn.execute_query(&query).await {
@@ -237,11 +251,16 @@ async fn database_pool_example() -> Result<(), ExampleError> {
 }
 
 async fn http_client_pool_example() -> Result<(), ExampleError> {
-    let client_pool = Arc::new(BundledPool::new(
-        1, // initial capacity
-        3, // maximum capacity
-        || HttpClient::new("https://api.example.com".to_string()),
-    ));
+    let client_pool = Arc::new(
+        BundledPool::builder()
+            .min_idle(1)
+            .max_idle(3)
+            .factory(|| HttpClient::new("https://api.example.com".to_string()))
+            // Clients whose connection timed out are replaced rather than handed out again
+            .validate(|client| client.healthy)
+            .build()
+            .map_err(|e| ExampleError::Error("Pool".to_string(), e.to_string()))?,
+    );
 
     println!(
         "Created HTTP client pool with {} available clients",
@@ -249,7 +268,7 @@ async fn http_client_pool_example() -> Result<(), ExampleError> {
     );
 
     // Simulate concurrent HTTP requests with some that will fail
-    let endpoints = vec!["/users", "/posts", "/timeout", "/comments", "/profile"];
+    let endpoints = ["/users", "/posts", "/timeout", "/comments", "/profile"];
     let mut handles = vec![];
 
     for (i, endpoint) in endpoints.iter().enumerate() {
@@ -257,7 +276,7 @@ async fn http_client_pool_example() -> Result<(), ExampleError> {
         let endpoint = endpoint.to_string();
 
         let handle = tokio::spawn(async move {
-            let mut client = pool.take();
+            let mut client = pool.take()?;
 
             match client.get(&endpoint).await {
                 Ok(response) => {
@@ -328,7 +347,7 @@ async fn concurrent_processing_example() -> Result<(), ExampleError> {
         let pool = Arc::clone(&buffer_pool);
 
         let handle = tokio::spawn(async move {
-            let mut buffer = pool.take();
+            let mut buffer = pool.take()?;
 
             match buffer.process_data(&chunk).await {
                 Ok(processed_size) => {
@@ -392,14 +411,15 @@ async fn stress_test_example() -> Result<(), ExampleError> {
         let handle = tokio::spawn(async move {
             // Each task performs multiple operations
             for j in 0..5 {
-                let mut conn = pool.take();
+                let mut conn = pool.take().expect("sync factory never fails");
                 let query = format!("SELECT * FROM table_{} WHERE id = {}", i, j);
 
-                if let Ok(result) = conn.execute_query(&query).await {
-                    if i % 20 == 0 && j == 0 {
-                        // Print some results to show progress
-                        println!("Task {}: {}", i, result);
-                    }
+                if let Ok(result) = conn.execute_query(&query).await
+                    && i % 20 == 0
+                    && j == 0
+                {
+                    // Print some results to show progress
+                    println!("Task {}: {}", i, result);
                 }
 
                 // Small delay between operations
@@ -437,7 +457,7 @@ async fn detached_object_example() -> Result<(), ExampleError> {
     println!("=== Detached Object Example ===");
 
     // Take an object and detach it
-    let client_item = pool.take();
+    let client_item = pool.take()?;
     let mut detached_client = client_item.detach(); // This removes it from pool tracking
 
     println!(
@@ -467,30 +487,32 @@ async fn try_take_example() -> Result<(), ExampleError> {
     let pool = Arc::new(BundledPool::new(
         0, // No initial objects
         2, // Small capacity
-        || ProcessingBuffer::new(),
+        ProcessingBuffer::new,
     ));
 
     println!("=== Try Take Example ===");
     println!("Pool starts empty - Available: {}", pool.available());
 
-    // try_take on empty pool returns None
+    // try_take on empty pool returns PoolError::Exhausted
     match pool.try_take() {
-        Some(_) => println!("Got an object (unexpected)"),
-        None => println!("No objects available (expected)"),
+        Ok(_) => println!("Got an object (unexpected)"),
+        Err(PoolError::Exhausted) => println!("No objects available (expected)"),
+        Err(e) => return Err(e.into()),
     }
 
     // take() will create a new object
-    let buffer1 = pool.take();
+    let buffer1 = pool.take()?;
     println!(
         "After take() - Available: {}, Used: {}",
         pool.available(),
         pool.used()
     );
 
-    // try_take still returns None because the object is in use
+    // try_take still fails because the object is in use
     match pool.try_take() {
-        Some(_) => println!("Got an object (unexpected)"),
-        None => println!("No objects available - object is in use"),
+        Ok(_) => println!("Got an object (unexpected)"),
+        Err(PoolError::Exhausted) => println!("No objects available - object is in use"),
+        Err(e) => return Err(e.into()),
     }
 
     // Drop the first buffer to return it to pool
@@ -503,12 +525,20 @@ async fn try_take_example() -> Result<(), ExampleError> {
 
     // Now try_take should succeed
     match pool.try_take() {
-        Some(buffer) => {
+        Ok(buffer) => {
             println!("Successfully got object with try_take()");
             drop(buffer);
         }
-        None => println!("Still no objects available (unexpected)"),
+        Err(e) => println!("Still no objects available (unexpected): {}", e),
+    }
+
+    // take_timeout() gives up once the latency budget is spent
+    let held = (pool.take_async().await?, pool.take_async().await?);
+    match pool.take_timeout(Duration::from_millis(10)).await {
+        Ok(_) => println!("Got an object (unexpected)"),
+        Err(e) => println!("No objects available - {}", e),
     }
+    drop(held);
 
     Ok(())
 }
@@ -529,7 +559,7 @@ async fn load_pattern_example() -> Result<(), ExampleError> {
     for i in 0..10 {
         let pool = Arc::clone(&pool);
         let handle = tokio::spawn(async move {
-            let mut client = pool.take();
+            let mut client = pool.take().expect("sync factory never fails");
             let result = client.get(&format!("/burst/{}", i)).await;
             println!("Burst request {}: {:?}", i, result.is_ok());
         });
@@ -556,7 +586,7 @@ async fn load_pattern_example() -> Result<(), ExampleError> {
         for i in 0..5 {
             let pool = Arc::clone(&pool);
             let handle = tokio::spawn(async move {
-                let mut client = pool.take();
+                let mut client = pool.take().expect("sync factory never fails");
                 let result = client.get(&format!("/sustained/{}/{}", batch, i)).await;
                 println!("Sustained request {}-{}: {:?}", batch, i, result.is_ok());
             });
@@ -585,9 +615,12 @@ async fn load_pattern_example() -> Result<(), ExampleError> {
 
 // Example demonstrating error handling with the pool
 async fn error_handling_example() -> Result<(), ExampleError> {
-    #[derive(Debug)]
+    #[derive(Debug, Resettable)]
     struct FlakyConnection {
+        #[reset(skip)]
         id: u32,
+        // Don't reset failure_rate to maintain realistic behavior
+        #[reset(skip)]
         failure_rate: f32,
         call_count: u32,
     }
@@ -616,13 +649,6 @@ async fn error_handling_example() -> Result<(), ExampleError> {
         }
     }
 
-    impl Resettable for FlakyConnection {
-        fn reset(&mut self) {
-            self.call_count = 0;
-            // Don't reset failure_rate to maintain realistic behavior
-        }
-    }
-
     let pool = Arc::new(BundledPool::new(2, 4, || {
         FlakyConnection::new(rand::random::<u32>() % 1000)
     }));
@@ -637,7 +663,7 @@ async fn error_handling_example() -> Result<(), ExampleError> {
     for i in 0..20 {
         let pool = Arc::clone(&pool);
         let handle = tokio::spawn(async move {
-            let mut conn = pool.take();
+            let mut conn = pool.take().map_err(|_| "Pool exhausted")?;
 
             // Retry logic
             for attempt in 1..=3 {
@@ -652,6 +678,8 @@ async fn error_handling_example() -> Result<(), ExampleError> {
                             sleep(Duration::from_millis(10)).await;
                         } else {
                             println!("Task {}: {} - giving up after {} attempts", i, e, attempt);
+                            // Don't hand a connection that keeps failing to the next task
+                            conn.discard();
                             return Err(e);
                         }
                     }
@@ -733,7 +761,7 @@ async fn handle_user_requests(
     pool: Arc<BundledPool<DatabaseConnection>>,
 ) -> Result<(), ExampleError> {
     for i in 0..3 {
-        let mut conn = pool.take();
+        let mut conn = pool.take()?;
         let query = format!("SELECT * FROM users WHERE id = {}", i);
         let result = conn.execute_query(&query).await?;
         println!("User Service: {}", result);
@@ -746,7 +774,7 @@ async fn handle_order_requests(
     pool: Arc<BundledPool<DatabaseConnection>>,
 ) -> Result<(), ExampleError> {
     for i in 0..3 {
-        let mut conn = pool.take();
+        let mut conn = pool.take()?;
         let query = format!("SELECT * FROM orders WHERE user_id = {}", i);
         let result = conn.execute_query(&query).await?;
         println!("Order Service: {}", result);
@@ -759,7 +787,7 @@ async fn handle_analytics_requests(
     pool: Arc<BundledPool<DatabaseConnection>>,
 ) -> Result<(), ExampleError> {
     for i in 0..2 {
-        let mut conn = pool.take();
+        let mut conn = pool.take()?;
         let query = format!("SELECT COUNT(*) FROM events WHERE date = '{}'", i);
         let result = conn.execute_query(&query).await?;
         println!("Analytics Service: {}", result);
@@ -0,0 +1,683 @@
+use crossbeam_queue::ArrayQueue;
+use std::convert::Infallible;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+#[cfg(feature = "rt")]
+use tokio::runtime::Handle;
+
+#[cfg(feature = "rt")]
+use crate::AsyncResettable;
+use crate::factory::Factory;
+#[cfg(feature = "rt")]
+use crate::pool::spawn_reset;
+use crate::pool::{BackgroundReset, Entry};
+#[cfg(feature = "rt")]
+use crate::telemetry;
+use crate::{BuildError, BundledPool, ResetOutcome, Resettable};
+
+pub(crate) type Hook<T> = Box<dyn Fn(&mut T) + Sync + Send + 'static>;
+// shared with the checked-out items, which still need it once the pool is gone.
+pub(crate) type DestroyHook<T> = Arc<dyn Fn(T) + Sync + Send + 'static>;
+pub(crate) type Check<T> = Box<dyn Fn(&T) -> bool + Sync + Send + 'static>;
+pub(crate) type RecoverHook<T> = Box<dyn Fn(&mut T) -> ResetOutcome + Sync + Send + 'static>;
+
+// the idle queue, the live-object cap and the factory of a validated builder.
+type Validated<T, E> = (ArrayQueue<Entry<T>>, usize, Factory<T, E>);
+
+/// Settings shared by every handle of a pool, other than its sizes and factory.
+pub(crate) struct PoolOptions<T> {
+    pub(crate) name: String,
+    pub(crate) on_return: Option<Hook<T>>,
+    pub(crate) on_destroy: Option<DestroyHook<T>>,
+    pub(crate) on_panic: Option<RecoverHook<T>>,
+    pub(crate) async_reset: Option<BackgroundReset<T>>,
+    pub(crate) lazy_reset: bool,
+    pub(crate) validate: Option<Check<T>>,
+    pub(crate) idle_timeout: Option<Duration>,
+    pub(crate) max_lifetime: Option<Duration>,
+    pub(crate) max_uses: Option<u64>,
+}
+
+impl<T> Default for PoolOptions<T> {
+    fn default() -> Self {
+        Self {
+            name: "default".to_string(),
+            on_return: None,
+            on_destroy: None,
+            on_panic: None,
+            async_reset: None,
+            lazy_reset: false,
+            validate: None,
+            idle_timeout: None,
+            max_lifetime: None,
+            max_uses: None,
+        }
+    }
+}
+
+/// Configures and creates a [`BundledPool`].
+///
+/// Obtained from [`BundledPool::builder`]. A factory and at least one of `max_idle` or `max_live` must be set;
+/// [`build`](Self::build) checks the settings and reports problems as a [`BuildError`] instead of panicking.
+///
+/// # Examples
+///
+/// ```
+/// use asyn_object_pool::{BundledPool, Resettable};
+///
+/// #[derive(Debug)]
+/// struct Buffer { data: Vec<u8> }
+///
+/// impl Resettable for Buffer {
+///     fn reset(&mut self) { self.data.clear(); }
+/// }
+///
+/// let pool = BundledPool::builder()
+///     .min_idle(2)
+///     .max_idle(4)
+///     .max_live(8)
+///     .factory(|| Buffer { data: Vec::with_capacity(1024) })
+///     .on_return(|buffer| buffer.data.shrink_to(4096))
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(pool.available(), 2);
+/// assert_eq!(pool.capacity(), 4);
+/// ```
+pub struct BundledPoolBuilder<T, E = Infallible> {
+    min_idle: usize,
+    max_idle: Option<usize>,
+    max_live: Option<usize>,
+    factory: Option<Factory<T, E>>,
+    options: PoolOptions<T>,
+}
+
+impl<T: Resettable> BundledPoolBuilder<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            min_idle: 0,
+            max_idle: None,
+            max_live: None,
+            factory: None,
+            options: PoolOptions::default(),
+        }
+    }
+}
+
+impl<T: Resettable, E> BundledPoolBuilder<T, E> {
+    /// Number of objects created up front. Defaults to 0.
+    pub fn min_idle(mut self, min_idle: usize) -> Self {
+        self.min_idle = min_idle;
+        self
+    }
+
+    /// Maximum number of idle objects kept in the pool; further returned objects are thrown away.
+    ///
+    /// Defaults to `max_live`.
+    pub fn max_idle(mut self, max_idle: usize) -> Self {
+        self.max_idle = Some(max_idle);
+        self
+    }
+
+    /// Maximum number of live objects (idle plus checked out) that [`BundledPool::take_async`] lets exist at once.
+    ///
+    /// Defaults to unbounded.
+    pub fn max_live(mut self, max_live: usize) -> Self {
+        self.max_live = Some(max_live);
+        self
+    }
+
+    /// Throws away objects that sat idle in the pool for longer than `idle_timeout`, e.g. connections the server
+    /// closes after a while. Defaults to keeping idle objects forever.
+    ///
+    /// Expired objects are thrown away when they would be checked out, or by [`BundledPool::evict_expired`].
+    pub fn idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.options.idle_timeout = Some(idle_timeout);
+        self
+    }
+
+    /// Throws away objects older than `max_lifetime`, counted from their creation. Defaults to no limit.
+    ///
+    /// Expired objects are thrown away when they are returned or would be checked out, or by
+    /// [`BundledPool::evict_expired`]; a checked-out object is never taken from its holder.
+    pub fn max_lifetime(mut self, max_lifetime: Duration) -> Self {
+        self.options.max_lifetime = Some(max_lifetime);
+        self
+    }
+
+    /// Throws away objects on return once they have been checked out `max_uses` times, so that the next checkout
+    /// gets a fresh one from the factory, e.g. for parsers that degrade with use. Defaults to no limit.
+    pub fn max_uses(mut self, max_uses: u64) -> Self {
+        self.options.max_uses = Some(max_uses);
+        self
+    }
+
+    /// Names the pool in its metrics and traces. Defaults to `"default"`.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.options.name = name.into();
+        self
+    }
+
+    /// Sets an infallible factory, like the one taken by [`BundledPool::new`].
+    pub fn factory<F>(self, create: F) -> BundledPoolBuilder<T>
+    where
+        F: Fn() -> T + Sync + Send + 'static,
+    {
+        self.with_factory(Factory::Sync(Box::new(move || Ok(create()))))
+    }
+
+    /// Sets a fallible factory, like the one taken by [`BundledPool::try_new`].
+    pub fn try_factory<E2, F>(self, create: F) -> BundledPoolBuilder<T, E2>
+    where
+        F: Fn() -> Result<T, E2> + Sync + Send + 'static,
+    {
+        self.with_factory(Factory::Sync(Box::new(create)))
+    }
+
+    /// Sets an asynchronous, fallible factory, like the one taken by [`BundledPool::new_async`].
+    ///
+    /// Pools with an asynchronous factory that pre-create objects must be built with
+    /// [`build_async`](Self::build_async).
+    pub fn async_factory<E2, F, Fut>(self, create: F) -> BundledPoolBuilder<T, E2>
+    where
+        F: Fn() -> Fut + Sync + Send + 'static,
+        Fut: Future<Output = Result<T, E2>> + Send + 'static,
+    {
+        self.with_factory(Factory::from_async(create))
+    }
+
+    /// Runs `on_return` on every object handed back to the pool, after [`Resettable::reset`]. With
+    /// [`lazy_reset`](Self::lazy_reset) the reset, and so the hook, only runs when the object is next checked out.
+    pub fn on_return<F>(mut self, on_return: F) -> Self
+    where
+        F: Fn(&mut T) + Sync + Send + 'static,
+    {
+        self.options.on_return = Some(Box::new(on_return));
+        self
+    }
+
+    /// Hands `on_destroy` every object the pool throws away, e.g. to close a connection cleanly: objects returned to
+    /// a full pool, expired or worn out, failing [`validate`](Self::validate), discarded by their reset or with
+    /// [`BundledPoolItem::discard`] / [`BundledPoolItem::mark_broken`], and the idle objects when the pool is closed
+    /// or dropped. The object's slot is freed once the hook returns; a panic in the hook is caught and the object
+    /// counted as thrown away all the same.
+    ///
+    /// Objects returned after the last handle to the pool was dropped go through the hook too, without being reset.
+    /// Objects whose reset or [`on_panic`](Self::on_panic) hook panicked are dropped without calling it. Replaces any
+    /// hook set with [`on_destroy_async`](Self::on_destroy_async).
+    ///
+    /// [`BundledPoolItem::discard`]: crate::BundledPoolItem::discard
+    /// [`BundledPoolItem::mark_broken`]: crate::BundledPoolItem::mark_broken
+    pub fn on_destroy<F>(mut self, on_destroy: F) -> Self
+    where
+        F: Fn(T) + Sync + Send + 'static,
+    {
+        self.options.on_destroy = Some(Arc::new(on_destroy));
+        self
+    }
+
+    /// Like [`on_destroy`](Self::on_destroy), for cleanup that needs `.await`: the future returned by `on_destroy`
+    /// runs in a task spawned on the tokio runtime of the thread throwing the object away, and the object's slot is
+    /// freed right away. Needs the `rt` feature, enabled by default.
+    ///
+    /// Outside a runtime there is nowhere to run the future, so the object is dropped without calling the hook; with
+    /// the `tracing` feature a warning is logged each time. Objects thrown away from plain threads, such as items
+    /// dropped there or pools closed there, are affected, so keep those within a runtime context (see
+    /// [`Handle::enter`](tokio::runtime::Handle::enter)) when the cleanup matters.
+    ///
+    /// Replaces any hook set with [`on_destroy`](Self::on_destroy).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use asyn_object_pool::{BundledPool, Resettable};
+    ///
+    /// #[derive(Debug)]
+    /// struct Connection;
+    ///
+    /// impl Connection {
+    ///     async fn goodbye(self) {}
+    /// }
+    ///
+    /// impl Resettable for Connection {
+    ///     fn reset(&mut self) {}
+    /// }
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// let pool = BundledPool::builder()
+    ///     .min_idle(1)
+    ///     .max_idle(1)
+    ///     .on_destroy_async(|conn: Connection| conn.goodbye())
+    ///     .factory(|| Connection)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let conn1 = pool.take().unwrap();
+    /// let conn2 = pool.take().unwrap();
+    /// drop(conn1);
+    /// drop(conn2); // The pool is full: says goodbye in the background
+    /// # }
+    /// ```
+    #[cfg(feature = "rt")]
+    pub fn on_destroy_async<F, Fut>(mut self, on_destroy: F) -> Self
+    where
+        T: Send + 'static,
+        F: Fn(T) -> Fut + Sync + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.options.on_destroy = Some(Arc::new(move |object| match Handle::try_current() {
+            Ok(runtime) => drop(runtime.spawn(on_destroy(object))),
+            Err(_) => telemetry::destroy_hook_skipped(),
+        }));
+        self
+    }
+
+    /// Runs `on_panic` instead of [`Resettable::try_reset`] on objects dropped while their thread is panicking, which
+    /// may have been left half-way through an update. The object goes back to the pool if `on_panic` returns
+    /// [`ResetOutcome::Keep`] and is thrown away otherwise, going through [`on_destroy`](Self::on_destroy) like any
+    /// discarded object.
+    ///
+    /// Without this, such objects are reset and returned like any other. Either way the hooks run while the thread
+    /// unwinds, so a panic in `on_panic` or in the reset is caught rather than raised again, which would abort the
+    /// process; the object is then dropped without calling `on_destroy`.
+    pub fn on_panic<F>(mut self, on_panic: F) -> Self
+    where
+        F: Fn(&mut T) -> ResetOutcome + Sync + Send + 'static,
+    {
+        self.options.on_panic = Some(Box::new(on_panic));
+        self
+    }
+
+    /// Throws away objects dropped while their thread is panicking rather than resetting and reusing them.
+    ///
+    /// Shorthand for `on_panic(|_| ResetOutcome::Discard)`.
+    pub fn discard_on_panic(self) -> Self {
+        self.on_panic(|_| ResetOutcome::Discard)
+    }
+
+    /// Runs `validate` on every idle object before handing it out. Objects that fail it are thrown away and the next
+    /// idle object is tried, or a new one created if there is none left, e.g. to weed out connections closed by the
+    /// peer while they sat in the pool.
+    ///
+    /// Objects fresh from the factory are not validated. If `validate` panics, the object is thrown away before the
+    /// panic reaches the caller of `take`.
+    pub fn validate<F>(mut self, validate: F) -> Self
+    where
+        F: Fn(&T) -> bool + Sync + Send + 'static,
+    {
+        self.options.validate = Some(Box::new(validate));
+        self
+    }
+
+    /// Resets returned objects with [`AsyncResettable::reset_async`] instead of [`Resettable::try_reset`].
+    ///
+    /// The reset runs in a task spawned on the tokio runtime of the thread dropping the item, and the object goes
+    /// back to the pool once it completes; it keeps its slot in the meantime. Items dropped outside a runtime fall back
+    /// to [`Resettable::try_reset`]. Needs the `rt` feature, enabled by default.
+    #[cfg(feature = "rt")]
+    pub fn async_reset(mut self) -> Self
+    where
+        T: AsyncResettable + Send + 'static,
+    {
+        self.options.async_reset = Some(spawn_reset::<T>);
+        self
+    }
+
+    /// Puts returned objects back into the pool as they are and resets them when they are next checked out, taking
+    /// the cost of [`Resettable::try_reset`] off the thread that drops the item.
+    ///
+    /// Objects that are never handed out again, because the pool is full or has been dropped, are not reset at all. An
+    /// object that cannot be reused is thrown away at checkout, and the next idle one is tried instead. The
+    /// [`on_return`](Self::on_return) hook runs at checkout too, right after the reset.
+    pub fn lazy_reset(mut self) -> Self {
+        self.options.lazy_reset = true;
+        self
+    }
+
+    /// Checks the settings and creates the pool, pre-creating `min_idle` objects.
+    ///
+    /// # Errors
+    ///
+    /// * [`BuildError::MissingFactory`] if no factory was set.
+    /// * [`BuildError::InvalidConfig`] if the sizes are inconsistent, if `max_uses` is 0, if both
+    ///   [`async_reset`](Self::async_reset) and [`lazy_reset`](Self::lazy_reset) were set, or if `min_idle > 0` with
+    ///   an asynchronous factory.
+    /// * [`BuildError::CreateFailed`] if the factory failed while pre-creating objects.
+    pub fn build(mut self) -> Result<BundledPool<T, E>, BuildError<E>> {
+        let (items, max_live, factory) = self.check_settings()?;
+        if self.min_idle > 0 {
+            let create = factory.as_sync().ok_or_else(|| {
+                BuildError::InvalidConfig(
+                    "an asynchronous factory can only pre-create objects in build_async()"
+                        .to_string(),
+                )
+            })?;
+            for _ in 0..self.min_idle {
+                pre_allocate(&items, create().map_err(BuildError::CreateFailed)?);
+            }
+        }
+
+        Ok(BundledPool::from_parts(
+            items,
+            self.min_idle,
+            max_live,
+            factory,
+            self.options,
+        ))
+    }
+
+    /// Like [`build`](Self::build), but awaits the factory to pre-create `min_idle` objects, so it also accepts an
+    /// asynchronous factory.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`build`](Self::build), except that asynchronous factories are always accepted.
+    pub async fn build_async(mut self) -> Result<BundledPool<T, E>, BuildError<E>> {
+        let (items, max_live, factory) = self.check_settings()?;
+        for _ in 0..self.min_idle {
+            pre_allocate(
+                &items,
+                factory.create().await.map_err(BuildError::CreateFailed)?,
+            );
+        }
+
+        Ok(BundledPool::from_parts(
+            items,
+            self.min_idle,
+            max_live,
+            factory,
+            self.options,
+        ))
+    }
+
+    fn with_factory<E2>(self, factory: Factory<T, E2>) -> BundledPoolBuilder<T, E2> {
+        BundledPoolBuilder {
+            min_idle: self.min_idle,
+            max_idle: self.max_idle,
+            max_live: self.max_live,
+            factory: Some(factory),
+            options: self.options,
+        }
+    }
+
+    fn check_settings(&mut self) -> Result<Validated<T, E>, BuildError<E>> {
+        let Some(factory) = self.factory.take() else {
+            return Err(BuildError::MissingFactory);
+        };
+        let max_live = self.max_live.unwrap_or(usize::MAX);
+        let Some(max_idle) = self.max_idle.or(self.max_live) else {
+            return Err(BuildError::InvalidConfig(
+                "max_idle or max_live must be set".to_string(),
+            ));
+        };
+
+        if max_idle == 0 {
+            return Err(BuildError::InvalidConfig(
+                "max_idle must be greater than 0".to_string(),
+            ));
+        }
+        if self.min_idle > max_idle {
+            return Err(BuildError::InvalidConfig(format!(
+                "min_idle ({}) must be <= max_idle ({})",
+                self.min_idle, max_idle
+            )));
+        }
+        if max_idle > max_live {
+            return Err(BuildError::InvalidConfig(format!(
+                "max_idle ({}) must be <= max_live ({})",
+                max_idle, max_live
+            )));
+        }
+
+        if self.options.max_uses == Some(0) {
+            return Err(BuildError::InvalidConfig(
+                "max_uses must be greater than 0".to_string(),
+            ));
+        }
+        if self.options.async_reset.is_some() && self.options.lazy_reset {
+            return Err(BuildError::InvalidConfig(
+                "async_reset and lazy_reset cannot be combined".to_string(),
+            ));
+        }
+
+        Ok((ArrayQueue::new(max_idle), max_live, factory))
+    }
+}
+
+#[inline]
+fn pre_allocate<T>(items: &ArrayQueue<Entry<T>>, object: T) {
+    // Pre-created objects take the first ids, in order
+    let entry = Entry::new(items.len() as u64, object);
+    // This should never fail because `check_settings` checked `min_idle <= max_idle`
+    if items.push(entry).is_err() {
+        unreachable!("invariant: items.len() always less than max_idle");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    #[cfg(feature = "async")]
+    use std::time::Duration;
+
+    #[derive(Debug, PartialEq)]
+    struct TestObj {
+        value: usize,
+    }
+
+    impl Resettable for TestObj {
+        fn reset(&mut self) {
+            self.value = 0;
+        }
+    }
+
+    fn invalid_config<E>(result: Result<BundledPool<TestObj, E>, BuildError<E>>) -> String {
+        match result {
+            Err(BuildError::InvalidConfig(msg)) => msg,
+            _ => panic!("expected BuildError::InvalidConfig"),
+        }
+    }
+
+    #[test]
+    fn test_build_pre_allocates_min_idle() {
+        let pool = BundledPool::builder()
+            .min_idle(2)
+            .max_idle(3)
+            .factory(|| TestObj { value: 1 })
+            .build()
+            .unwrap();
+        assert_eq!(pool.available(), 2);
+        assert_eq!(pool.capacity(), 3);
+    }
+
+    #[test]
+    fn test_build_max_idle_defaults_to_max_live() {
+        let pool = BundledPool::builder()
+            .max_live(5)
+            .factory(|| TestObj { value: 1 })
+            .build()
+            .unwrap();
+        assert_eq!(pool.capacity(), 5);
+    }
+
+    #[test]
+    fn test_build_missing_factory() {
+        let result = BundledPool::<TestObj>::builder().max_idle(2).build();
+        assert!(matches!(result, Err(BuildError::MissingFactory)));
+    }
+
+    #[test]
+    fn test_build_rejects_invalid_sizes() {
+        let msg = invalid_config(
+            BundledPool::builder()
+                .factory(|| TestObj { value: 1 })
+                .build(),
+        );
+        assert_eq!(msg, "max_idle or max_live must be set");
+
+        let msg = invalid_config(
+            BundledPool::builder()
+                .max_idle(0)
+                .factory(|| TestObj { value: 1 })
+                .build(),
+        );
+        assert_eq!(msg, "max_idle must be greater than 0");
+
+        let msg = invalid_config(
+            BundledPool::builder()
+                .min_idle(3)
+                .max_idle(2)
+                .factory(|| TestObj { value: 1 })
+                .build(),
+        );
+        assert_eq!(msg, "min_idle (3) must be <= max_idle (2)");
+
+        let msg = invalid_config(
+            BundledPool::builder()
+                .max_idle(4)
+                .max_live(2)
+                .factory(|| TestObj { value: 1 })
+                .build(),
+        );
+        assert_eq!(msg, "max_idle (4) must be <= max_live (2)");
+
+        let msg = invalid_config(
+            BundledPool::builder()
+                .max_idle(1)
+                .max_uses(0)
+                .factory(|| TestObj { value: 1 })
+                .build(),
+        );
+        assert_eq!(msg, "max_uses must be greater than 0");
+    }
+
+    #[test]
+    fn test_build_create_failed() {
+        let result = BundledPool::builder()
+            .min_idle(1)
+            .max_idle(2)
+            .try_factory(|| Err::<TestObj, _>("refused"))
+            .build();
+        assert!(matches!(result, Err(BuildError::CreateFailed("refused"))));
+    }
+
+    #[test]
+    fn test_build_rejects_async_factory_with_min_idle() {
+        let msg = invalid_config(
+            BundledPool::builder()
+                .min_idle(1)
+                .max_idle(2)
+                .async_factory(|| async { Ok::<_, ()>(TestObj { value: 1 }) })
+                .build(),
+        );
+        assert_eq!(
+            msg,
+            "an asynchronous factory can only pre-create objects in build_async()"
+        );
+    }
+
+    #[cfg(feature = "rt")]
+    impl AsyncResettable for TestObj {
+        async fn reset_async(&mut self) -> ResetOutcome {
+            self.reset();
+            ResetOutcome::Keep
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "rt")]
+    fn test_build_rejects_async_and_lazy_reset() {
+        let msg = invalid_config(
+            BundledPool::builder()
+                .max_idle(2)
+                .factory(|| TestObj { value: 1 })
+                .async_reset()
+                .lazy_reset()
+                .build(),
+        );
+        assert_eq!(msg, "async_reset and lazy_reset cannot be combined");
+    }
+
+    #[tokio::test]
+    async fn test_build_async() {
+        let pool = BundledPool::builder()
+            .min_idle(2)
+            .max_idle(2)
+            .async_factory(|| async { Ok::<_, ()>(TestObj { value: 1 }) })
+            .build_async()
+            .await
+            .unwrap();
+        assert_eq!(pool.available(), 2);
+    }
+
+    #[test]
+    fn test_on_return_runs_after_reset() {
+        let returned = Arc::new(AtomicUsize::new(0));
+        let pool = BundledPool::builder()
+            .max_idle(2)
+            .factory(|| TestObj { value: 1 })
+            .on_return({
+                let returned = Arc::clone(&returned);
+                move |obj: &mut TestObj| {
+                    assert_eq!(obj.value, 0);
+                    obj.value = 10;
+                    returned.fetch_add(1, Ordering::Relaxed);
+                }
+            })
+            .build()
+            .unwrap();
+
+        let mut item = pool.take().unwrap();
+        item.value = 5;
+        drop(item);
+        assert_eq!(returned.load(Ordering::Relaxed), 1);
+        assert_eq!(pool.take().unwrap().value, 10);
+    }
+
+    #[test]
+    fn test_on_return_runs_after_lazy_reset() {
+        let returned = Arc::new(AtomicUsize::new(0));
+        let pool = BundledPool::builder()
+            .max_idle(2)
+            .factory(|| TestObj { value: 1 })
+            .on_return({
+                let returned = Arc::clone(&returned);
+                move |obj: &mut TestObj| {
+                    assert_eq!(obj.value, 0);
+                    obj.value = 10;
+                    returned.fetch_add(1, Ordering::Relaxed);
+                }
+            })
+            .lazy_reset()
+            .build()
+            .unwrap();
+
+        let mut item = pool.take().unwrap();
+        item.value = 5;
+        drop(item);
+        // Deferred along with the reset
+        assert_eq!(returned.load(Ordering::Relaxed), 0);
+        assert_eq!(pool.take().unwrap().value, 10);
+        assert_eq!(returned.load(Ordering::Relaxed), 1);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_max_live_caps_take_async() {
+        let pool = BundledPool::builder()
+            .max_idle(1)
+            .max_live(2)
+            .factory(|| TestObj { value: 1 })
+            .build()
+            .unwrap();
+
+        let _item1 = pool.take_async().await.unwrap();
+        let _item2 = pool.take_async().await.unwrap();
+        let result = pool.take_timeout(Duration::from_millis(10)).await;
+        assert!(matches!(result, Err(crate::PoolError::Timeout)));
+    }
+}
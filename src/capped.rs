@@ -0,0 +1,218 @@
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::fmt::{Debug, Formatter};
+use std::hash::{BuildHasher, Hash};
+use std::ops::{Deref, DerefMut};
+
+use crate::{ResetOutcome, Resettable};
+
+/// A buffer whose allocation can be inspected and given back, used by [`CapacityCapped`].
+///
+/// Implemented for the std collections that track a capacity; implement it for your own buffer types by forwarding to
+/// their inner collections.
+pub trait Growable {
+    /// Returns the number of elements the buffer can hold without reallocating.
+    fn capacity(&self) -> usize;
+
+    /// Shrinks the allocation to hold at least `max(len, capacity)` elements.
+    fn shrink_to(&mut self, capacity: usize);
+}
+
+macro_rules! impl_growable {
+    ($($ty:ty => [$($generics:tt)*] where [$($bounds:tt)*]),+ $(,)?) => {
+        $(
+            impl<$($generics)*> Growable for $ty where $($bounds)* {
+                #[inline]
+                fn capacity(&self) -> usize {
+                    <$ty>::capacity(self)
+                }
+
+                #[inline]
+                fn shrink_to(&mut self, capacity: usize) {
+                    <$ty>::shrink_to(self, capacity);
+                }
+            }
+        )+
+    };
+}
+
+impl_growable!(
+    String => [] where [],
+    Vec<T> => [T] where [],
+    VecDeque<T> => [T] where [],
+    BinaryHeap<T> => [T] where [T: Ord],
+    HashMap<K, V, S> => [K, V, S] where [K: Eq + Hash, S: BuildHasher],
+    HashSet<T, S> => [T, S] where [T: Eq + Hash, S: BuildHasher],
+);
+
+/// Wraps a growable buffer so that resetting it also gives memory back once it has grown past `N` elements.
+///
+/// A pooled buffer keeps whatever allocation its largest use needed, so a single huge request would otherwise inflate
+/// the pool's footprint for good. On [`reset`](Resettable::reset), the inner value is reset first and its allocation
+/// is then shrunk to `N` if it is larger. Buffers at or below the threshold keep their capacity.
+///
+/// The wrapper dereferences to the inner value.
+///
+/// # Examples
+///
+/// ```
+/// use asyn_object_pool::{BundledPool, CapacityCapped, Resettable};
+///
+/// let pool = BundledPool::new(1, 4, || CapacityCapped::<Vec<u8>, 1024>::new(Vec::with_capacity(512)));
+///
+/// let mut buffer = pool.take().unwrap();
+/// buffer.extend_from_slice(&[0; 1_000_000]);
+/// drop(buffer);
+///
+/// let buffer = pool.take().unwrap();
+/// assert!(buffer.is_empty());
+/// assert!(buffer.capacity() < 1_000_000);
+/// ```
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct CapacityCapped<T, const N: usize>(T);
+
+impl<T, const N: usize> CapacityCapped<T, N> {
+    /// Wraps `inner`. Its current capacity is left alone until the first reset.
+    #[inline]
+    pub fn new(inner: T) -> Self {
+        Self(inner)
+    }
+
+    /// Returns the inner value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: Resettable + Growable, const N: usize> Resettable for CapacityCapped<T, N> {
+    fn reset(&mut self) {
+        self.0.reset();
+        self.cap();
+    }
+
+    fn try_reset(&mut self) -> ResetOutcome {
+        let outcome = self.0.try_reset();
+        self.cap();
+        outcome
+    }
+}
+
+impl<T: Growable, const N: usize> CapacityCapped<T, N> {
+    #[inline]
+    fn cap(&mut self) {
+        if self.0.capacity() > N {
+            self.0.shrink_to(N);
+        }
+    }
+}
+
+impl<T, const N: usize> Deref for CapacityCapped<T, N> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T, const N: usize> DerefMut for CapacityCapped<T, N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T, const N: usize> AsRef<T> for CapacityCapped<T, N> {
+    #[inline]
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T, const N: usize> AsMut<T> for CapacityCapped<T, N> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: Debug, const N: usize> Debug for CapacityCapped<T, N> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shrinks_past_threshold() {
+        let mut buffer = CapacityCapped::<Vec<u8>, 64>::new(Vec::new());
+        buffer.extend_from_slice(&[1; 10_000]);
+        buffer.reset();
+        assert!(buffer.is_empty());
+        assert!(buffer.capacity() >= 64);
+        assert!(buffer.capacity() < 10_000);
+    }
+
+    #[test]
+    fn test_keeps_capacity_below_threshold() {
+        let mut buffer = CapacityCapped::<String, 1024>::new(String::with_capacity(512));
+        buffer.push_str("hello");
+        buffer.reset();
+        assert!(buffer.is_empty());
+        assert!(buffer.capacity() >= 512);
+    }
+
+    #[test]
+    fn test_capped_collections() {
+        let mut map = CapacityCapped::<HashMap<u32, u32>, 16>::new(HashMap::new());
+        map.extend((0..10_000).map(|i| (i, i)));
+        map.reset();
+        assert!(map.is_empty());
+        assert!(map.capacity() < 10_000);
+
+        let mut deque = CapacityCapped::<VecDeque<u32>, 16>::default();
+        deque.extend(0..10_000);
+        deque.reset();
+        assert!(deque.capacity() < 10_000);
+    }
+
+    // a user buffer that opts in by forwarding to its inner collection.
+    #[derive(Debug, Default)]
+    struct Frame {
+        bytes: Vec<u8>,
+        sequence: u64,
+    }
+
+    impl Resettable for Frame {
+        fn reset(&mut self) {
+            self.bytes.clear();
+            self.sequence = 0;
+        }
+    }
+
+    impl Growable for Frame {
+        fn capacity(&self) -> usize {
+            self.bytes.capacity()
+        }
+
+        fn shrink_to(&mut self, capacity: usize) {
+            self.bytes.shrink_to(capacity);
+        }
+    }
+
+    #[test]
+    fn test_capped_user_type() {
+        let mut frame = CapacityCapped::<Frame, 128>::default();
+        frame.bytes.resize(1 << 20, 0);
+        frame.sequence = 7;
+        frame.reset();
+        assert_eq!(frame.sequence, 0);
+        assert!(frame.bytes.capacity() < 1 << 20);
+
+        let inner = frame.into_inner();
+        assert!(inner.bytes.is_empty());
+    }
+}
@@ -0,0 +1,122 @@
+use std::convert::Infallible;
+use std::fmt::{Display, Formatter};
+
+/// Error returned when an object could not be acquired from a `BundledPool`.
+///
+/// The variants let callers tell an empty pool apart from a pool that has been shut down or a factory that failed.
+/// `E` is the error type of the pool's factory; it is [`Infallible`] for pools built with `BundledPool::new`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PoolError<E = Infallible> {
+    /// The pool has been closed and no longer hands out objects.
+    Closed,
+    /// No object became available before the timeout or deadline expired.
+    Timeout,
+    /// No idle object was available and the pool may not create one here: `try_take` never allocates, and `take`
+    /// cannot await an asynchronous factory.
+    Exhausted,
+    /// The factory failed to create a new object.
+    CreateFailed(E),
+}
+
+impl<E: Display> Display for PoolError<E> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            PoolError::Closed => f.write_str("the pool is closed"),
+            PoolError::Timeout => f.write_str("timed out waiting for a pooled object"),
+            PoolError::Exhausted => f.write_str("no pooled object is available"),
+            PoolError::CreateFailed(e) => write!(f, "failed to create a pooled object: {e}"),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for PoolError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PoolError::CreateFailed(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Error returned by `BundledPoolBuilder::build` when the pool could not be created.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BuildError<E = Infallible> {
+    /// No factory was set on the builder.
+    MissingFactory,
+    /// The settings are inconsistent; the message says which ones.
+    InvalidConfig(String),
+    /// The factory failed while pre-creating objects.
+    CreateFailed(E),
+}
+
+impl<E: Display> Display for BuildError<E> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            BuildError::MissingFactory => f.write_str("no factory was set"),
+            BuildError::InvalidConfig(msg) => write!(f, "invalid pool configuration: {msg}"),
+            BuildError::CreateFailed(e) => write!(f, "failed to create a pooled object: {e}"),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for BuildError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BuildError::CreateFailed(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+    use std::io;
+
+    #[test]
+    fn test_pool_error_display() {
+        assert_eq!(
+            PoolError::<Infallible>::Closed.to_string(),
+            "the pool is closed"
+        );
+        assert_eq!(
+            PoolError::<Infallible>::Timeout.to_string(),
+            "timed out waiting for a pooled object"
+        );
+        assert_eq!(
+            PoolError::<Infallible>::Exhausted.to_string(),
+            "no pooled object is available"
+        );
+        assert_eq!(
+            PoolError::CreateFailed("refused").to_string(),
+            "failed to create a pooled object: refused"
+        );
+    }
+
+    #[test]
+    fn test_pool_error_source() {
+        let err = PoolError::CreateFailed(io::Error::other("refused"));
+        assert_eq!(err.source().unwrap().to_string(), "refused");
+        assert!(PoolError::<io::Error>::Timeout.source().is_none());
+    }
+
+    #[test]
+    fn test_build_error_display() {
+        assert_eq!(
+            BuildError::<Infallible>::MissingFactory.to_string(),
+            "no factory was set"
+        );
+        assert_eq!(
+            BuildError::<Infallible>::InvalidConfig("max_idle must be greater than 0".to_string())
+                .to_string(),
+            "invalid pool configuration: max_idle must be greater than 0"
+        );
+        assert_eq!(
+            BuildError::CreateFailed("refused").to_string(),
+            "failed to create a pooled object: refused"
+        );
+    }
+}
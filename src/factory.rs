@@ -0,0 +1,47 @@
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+
+type CreateFuture<T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'static>>;
+
+/// The function a `BundledPool` uses to build new objects.
+pub(crate) enum Factory<T, E> {
+    Sync(Box<dyn Fn() -> Result<T, E> + Sync + Send + 'static>),
+    Async(Box<dyn Fn() -> CreateFuture<T, E> + Sync + Send + 'static>),
+}
+
+impl<T, E> Factory<T, E> {
+    pub(crate) fn from_async<F, Fut>(create: F) -> Self
+    where
+        F: Fn() -> Fut + Sync + Send + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+    {
+        Factory::Async(Box::new(move || Box::pin(create())))
+    }
+
+    /// Creates an object, awaiting the factory if it is asynchronous.
+    pub(crate) async fn create(&self) -> Result<T, E> {
+        match self {
+            Factory::Sync(create) => create(),
+            Factory::Async(create) => create().await,
+        }
+    }
+
+    /// The factory as a plain function, or `None` if it is asynchronous.
+    #[inline]
+    pub(crate) fn as_sync(&self) -> Option<&(dyn Fn() -> Result<T, E> + Sync + Send + 'static)> {
+        match self {
+            Factory::Sync(create) => Some(create.as_ref()),
+            Factory::Async(_) => None,
+        }
+    }
+}
+
+impl<T, E> Debug for Factory<T, E> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            Factory::Sync(_) => f.write_str("Box<dyn Fn() -> T>"),
+            Factory::Async(_) => f.write_str("Box<dyn Fn() -> impl Future<Output = T>>"),
+        }
+    }
+}
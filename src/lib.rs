@@ -1,5 +1,22 @@
+// lets the code generated by `#[derive(Resettable)]` name this crate from within it, e.g. in tests.
+extern crate self as asyn_object_pool;
+
+mod builder;
+mod capped;
+mod error;
+mod factory;
 mod pool;
+mod prometheus;
 mod reset;
+mod stats;
+mod telemetry;
 
+pub use self::builder::BundledPoolBuilder;
+pub use self::capped::{CapacityCapped, Growable};
+pub use self::error::{BuildError, PoolError};
 pub use self::pool::{BundledPool, BundledPoolItem};
-pub use self::reset::Resettable;
+pub use self::prometheus::PrometheusExporter;
+pub use self::reset::{AsyncResettable, ResetOutcome, Resettable};
+pub use self::stats::{DurationStats, PoolStats};
+#[cfg(feature = "derive")]
+pub use asyn_object_pool_derive::Resettable;
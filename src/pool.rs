@@ -1,9 +1,32 @@
 use crossbeam_queue::ArrayQueue;
+use std::convert::Infallible;
 use std::fmt::{Debug, Formatter};
+use std::future::Future;
 use std::ops::{Deref, DerefMut};
+use std::panic::{self, AssertUnwindSafe};
+#[cfg(feature = "rt")]
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Weak};
-
-use crate::Resettable;
+use std::thread;
+use std::time::{Duration, Instant};
+#[cfg(feature = "rt")]
+use tokio::runtime::Handle;
+#[cfg(feature = "async")]
+use tokio::sync::Notify;
+#[cfg(feature = "rt")]
+use tokio::task::AbortHandle;
+#[cfg(feature = "rt")]
+use tokio::time::MissedTickBehavior;
+
+#[cfg(feature = "rt")]
+use crate::AsyncResettable;
+use crate::builder::{DestroyHook, PoolOptions};
+use crate::factory::Factory;
+use crate::prometheus::{Sample, Sampled};
+use crate::stats::{DiscardReason, PoolCounters};
+use crate::telemetry::Telemetry;
+use crate::{BuildError, BundledPoolBuilder, PoolError, PoolStats, ResetOutcome, Resettable};
 
 /// A lock-free, thread-safe, sized object pool.
 ///
@@ -28,7 +51,7 @@ use crate::Resettable;
 /// let pool = BundledPool::new(2, 5, || Connection { id: 1 });
 ///
 /// // Take an object from the pool
-/// let conn = pool.take();
+/// let conn = pool.take().unwrap();
 /// assert_eq!(conn.id, 1);
 ///
 /// // Object is automatically returned when dropped
@@ -39,7 +62,7 @@ use crate::Resettable;
 /// Using try_take for non-blocking access:
 ///
 /// ```
-/// use asyn_object_pool::{BundledPool, Resettable};
+/// use asyn_object_pool::{BundledPool, PoolError, Resettable};
 ///
 /// #[derive(Debug)]
 /// struct Resource { value: i32 }
@@ -50,8 +73,8 @@ use crate::Resettable;
 ///
 /// let pool = BundledPool::new(0, 1, || Resource { value: 42 });
 ///
-/// // Pool is empty, try_take returns None
-/// assert!(pool.try_take().is_none());
+/// // Pool is empty, try_take returns an error
+/// assert!(matches!(pool.try_take(), Err(PoolError::Exhausted)));
 /// ```
 ///
 /// this pool begins with an initial capacity and will continue creating new objects on request when none are available.
@@ -60,14 +83,24 @@ use crate::Resettable;
 ///
 /// if, during an attempted return, a pool already has `maximum_capacity` objects in the pool, the pool will throw away
 /// that object.
-pub struct BundledPool<T: Resettable> {
+///
+/// `maximum_capacity` is also the cap on live objects (idle plus checked out) honoured by
+/// [`take_async`](BundledPool::take_async), which waits for an object to be returned instead of creating one past the
+/// cap.
+///
+/// Objects are built by a factory, which may be fallible ([`try_new`](BundledPool::try_new)) or asynchronous
+/// ([`new_async`](BundledPool::new_async)); `E` is the factory's error type. [`builder`](BundledPool::builder)
+/// configures the idle and live limits separately.
+pub struct BundledPool<T: Resettable, E = Infallible> {
     data: Arc<PoolData<T>>,
+    factory: Arc<Factory<T, E>>,
 }
 
-impl<T: Resettable + Debug> Debug for BundledPool<T> {
+impl<T: Resettable + Debug, E> Debug for BundledPool<T, E> {
     fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
         f.debug_struct("BundledPool")
             .field("data", &self.data)
+            .field("create", &self.factory)
             .finish()
     }
 }
@@ -106,37 +139,224 @@ impl<T: Resettable> BundledPool<T> {
         maximum_capacity: usize,
         create: F,
     ) -> BundledPool<T> {
+        match Self::try_new(initial_capacity, maximum_capacity, move || Ok(create())) {
+            Ok(pool) => pool,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Returns a [`BundledPoolBuilder`] for configuring a pool by name rather than by position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use asyn_object_pool::{BuildError, BundledPool, Resettable};
+    ///
+    /// #[derive(Debug)]
+    /// struct Counter { count: usize }
+    ///
+    /// impl Resettable for Counter {
+    ///     fn reset(&mut self) { self.count = 0; }
+    /// }
+    ///
+    /// let pool = BundledPool::builder()
+    ///     .min_idle(1)
+    ///     .max_idle(3)
+    ///     .factory(|| Counter { count: 0 })
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(pool.available(), 1);
+    ///
+    /// // Inconsistent settings are reported instead of panicking.
+    /// let err = BundledPool::builder()
+    ///     .min_idle(4)
+    ///     .max_idle(3)
+    ///     .factory(|| Counter { count: 0 })
+    ///     .build()
+    ///     .unwrap_err();
+    /// assert!(matches!(err, BuildError::InvalidConfig(_)));
+    /// ```
+    #[inline]
+    pub fn builder() -> BundledPoolBuilder<T> {
+        BundledPoolBuilder::new()
+    }
+}
+
+impl<T: Resettable, E> BundledPool<T, E> {
+    /// Creates a new `BundledPool<T, E>` whose factory can fail.
+    ///
+    /// Returns the factory's error if one of the `initial_capacity` objects could not be created. Later creation
+    /// failures are reported by [`take`](Self::take) and [`take_async`](Self::take_async) as
+    /// [`PoolError::CreateFailed`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `initial_capacity > maximum_capacity`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use asyn_object_pool::{BundledPool, PoolError, Resettable};
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    ///
+    /// #[derive(Debug)]
+    /// struct Connection;
+    ///
+    /// impl Resettable for Connection {
+    ///     fn reset(&mut self) {}
+    /// }
+    ///
+    /// static DOWN: AtomicBool = AtomicBool::new(false);
+    ///
+    /// let pool = BundledPool::try_new(1, 2, || {
+    ///     if DOWN.load(Ordering::Relaxed) {
+    ///         Err("connection refused")
+    ///     } else {
+    ///         Ok(Connection)
+    ///     }
+    /// })
+    /// .unwrap();
+    ///
+    /// let _conn = pool.take().unwrap();
+    /// DOWN.store(true, Ordering::Relaxed);
+    /// assert_eq!(pool.take().unwrap_err(), PoolError::CreateFailed("connection refused"));
+    /// ```
+    pub fn try_new<F>(
+        initial_capacity: usize,
+        maximum_capacity: usize,
+        create: F,
+    ) -> Result<BundledPool<T, E>, E>
+    where
+        F: Fn() -> Result<T, E> + Sync + Send + 'static,
+    {
+        Self::assert_capacities(initial_capacity, maximum_capacity);
+        BundledPoolBuilder::new()
+            .min_idle(initial_capacity)
+            .max_idle(maximum_capacity)
+            .max_live(maximum_capacity)
+            .try_factory(create)
+            .build()
+            .map_err(Self::into_create_error)
+    }
+
+    /// Creates a new `BundledPool<T, E>` whose factory is asynchronous and can fail.
+    ///
+    /// The `initial_capacity` objects are created before this returns. Because the factory has to be awaited,
+    /// [`take`](Self::take) can only hand out idle objects and returns [`PoolError::Exhausted`] when there are none;
+    /// use [`take_async`](Self::take_async) to create objects on demand.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `initial_capacity > maximum_capacity`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use asyn_object_pool::{BundledPool, Resettable};
+    ///
+    /// #[derive(Debug)]
+    /// struct Connection { id: u32 }
+    ///
+    /// impl Resettable for Connection {
+    ///     fn reset(&mut self) {}
+    /// }
+    ///
+    /// async fn connect() -> Result<Connection, std::io::Error> {
+    ///     Ok(Connection { id: 1 })
+    /// }
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let pool = BundledPool::new_async(1, 4, connect).await?;
+    ///
+    /// // The pre-created connection is idle, so even `take` can hand it out
+    /// let conn = pool.take()?;
+    /// assert_eq!(conn.id, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn new_async<F, Fut>(
+        initial_capacity: usize,
+        maximum_capacity: usize,
+        create: F,
+    ) -> Result<BundledPool<T, E>, E>
+    where
+        F: Fn() -> Fut + Sync + Send + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+    {
+        Self::assert_capacities(initial_capacity, maximum_capacity);
+        BundledPoolBuilder::new()
+            .min_idle(initial_capacity)
+            .max_idle(maximum_capacity)
+            .max_live(maximum_capacity)
+            .async_factory(create)
+            .build_async()
+            .await
+            .map_err(Self::into_create_error)
+    }
+
+    fn assert_capacities(initial_capacity: usize, maximum_capacity: usize) {
         assert!(
             initial_capacity <= maximum_capacity,
             "initial_capacity ({}) must be <= maximum_capacity ({})",
             initial_capacity,
             maximum_capacity
         );
+    }
 
-        let items = ArrayQueue::new(maximum_capacity);
-
-        // Pre-allocate objects more efficiently
-        for _ in 0..initial_capacity {
-            let obj = create();
-            // This should never fail due to our assertion above
-            if items.push(obj).is_err() {
-                unreachable!("invariant: items.len() always less than maximum_capacity");
-            }
+    // the positional constructors report only factory errors; anything else is a panic, as before the builder.
+    fn into_create_error(err: BuildError<E>) -> E {
+        match err {
+            BuildError::CreateFailed(e) => e,
+            BuildError::InvalidConfig(msg) => panic!("{}", msg),
+            BuildError::MissingFactory => unreachable!("invariant: a factory is always set"),
         }
+    }
 
+    #[cfg_attr(not(feature = "rt"), allow(unused_variables))]
+    pub(crate) fn from_parts(
+        items: ArrayQueue<Entry<T>>,
+        min_idle: usize,
+        max_live: usize,
+        factory: Factory<T, E>,
+        options: PoolOptions<T>,
+    ) -> BundledPool<T, E> {
         let data = PoolData {
+            live: AtomicUsize::new(items.len()),
+            next_id: AtomicU64::new(items.len() as u64),
+            counters: PoolCounters::new(items.len() as u64),
+            telemetry: Telemetry::new(&options.name, items.len()),
             items,
-            create: Box::new(create),
+            #[cfg(feature = "rt")]
+            min_idle,
+            max_live,
+            #[cfg(feature = "async")]
+            returned: Notify::new(),
+            #[cfg(feature = "async")]
+            resetting: AtomicUsize::new(0),
+            #[cfg(feature = "async")]
+            settled: Notify::new(),
+            closed: AtomicBool::new(false),
+            #[cfg(feature = "rt")]
+            maintenance: Mutex::new(None),
+            options,
         };
 
         BundledPool {
             data: Arc::new(data),
+            factory: Arc::new(factory),
         }
     }
 
     /// Takes an item from the pool, creating one if none are available.
     ///
-    /// This method always succeeds but may allocate a new object if the pool is empty.
+    /// This method may allocate a new object if the pool is empty. It never waits, so it does not honour the
+    /// live-object cap; use [`take_async`](Self::take_async) for a hard bound.
+    ///
+    /// # Errors
+    ///
+    /// * [`PoolError::CreateFailed`] if the factory failed to create a new object.
+    /// * [`PoolError::Exhausted`] if the pool is empty and its factory is asynchronous.
     ///
     /// # Examples
     ///
@@ -152,35 +372,161 @@ impl<T: Resettable> BundledPool<T> {
     ///
     /// let pool = BundledPool::new(1, 2, || Item { id: 42 });
     ///
-    /// let item1 = pool.take();
-    /// let item2 = pool.take(); // Creates new object since pool is empty
+    /// let item1 = pool.take().unwrap();
+    /// let item2 = pool.take().unwrap(); // Creates new object since pool is empty
     ///
     /// assert_eq!(item1.id, 42);
     /// assert_eq!(item2.id, 42);
     /// ```
-    #[must_use]
     #[inline]
-    pub fn take(&self) -> BundledPoolItem<T> {
-        let object = self
-            .data
-            .items
-            .pop()
-            .unwrap_or_else(|| (self.data.create)());
+    pub fn take(&self) -> Result<BundledPoolItem<T>, PoolError<E>> {
+        if self.data.is_closed() {
+            return Err(PoolError::Closed);
+        }
+        if let Some(entry) = self.data.checkout() {
+            return Ok(self.wrap(entry));
+        }
 
-        BundledPoolItem {
-            data: Arc::downgrade(&self.data),
-            object: Some(object),
+        let create = self.factory.as_sync().ok_or(PoolError::Exhausted)?;
+        self.data.live.fetch_add(1, Ordering::AcqRel);
+        let slot = SlotGuard(&self.data);
+        let object = create().map_err(PoolError::CreateFailed)?;
+        slot.keep();
+
+        self.wrap_created(object)
+    }
+
+    /// Takes an item from the pool, waiting for one to be returned once the live-object cap is reached.
+    ///
+    /// An idle object is handed out if there is one, otherwise a new one is created as long as fewer than
+    /// `maximum_capacity` objects are alive (idle plus checked out). Past that point the task is parked until a
+    /// `BundledPoolItem` is dropped or detached; every release wakes exactly one waiter.
+    ///
+    /// This and the other `async` methods need the `async` feature, enabled by default (and by `rt`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PoolError::CreateFailed`] if the factory failed to create a new object. The slot reserved for it is
+    /// given back, so the failure does not count against the cap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use asyn_object_pool::{BundledPool, Resettable};
+    ///
+    /// #[derive(Debug)]
+    /// struct Item { id: u32 }
+    ///
+    /// impl Resettable for Item {
+    ///     fn reset(&mut self) {}
+    /// }
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// let pool = BundledPool::new(0, 1, || Item { id: 7 });
+    ///
+    /// let item = pool.take_async().await.unwrap();
+    /// assert_eq!(item.id, 7);
+    ///
+    /// // The cap is reached; the next `take_async` resolves once `item` is dropped.
+    /// drop(item);
+    /// let item = pool.take_async().await.unwrap();
+    /// assert_eq!(item.id, 7);
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn take_async(&self) -> Result<BundledPoolItem<T>, PoolError<E>> {
+        let started = Instant::now();
+        loop {
+            let notified = self.data.returned.notified();
+            // Register interest before inspecting the pool so that a release
+            // between the check and `.await` is never lost.
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if self.data.is_closed() {
+                return Err(PoolError::Closed);
+            }
+            if let Some(entry) = self.data.checkout() {
+                self.data.waited(started.elapsed());
+                return Ok(self.wrap(entry));
+            }
+            if self.data.try_reserve() {
+                // Dropping this future mid-creation gives the slot back too.
+                let slot = SlotGuard(&self.data);
+                let object = self
+                    .factory
+                    .create()
+                    .await
+                    .map_err(PoolError::CreateFailed)?;
+                slot.keep();
+                let item = self.wrap_created(object)?;
+                self.data.waited(started.elapsed());
+                return Ok(item);
+            }
+            notified.await;
         }
     }
 
+    /// Like [`take_async`](Self::take_async), but gives up after `timeout`.
+    ///
+    /// Returns [`PoolError::Timeout`] if no object became available in time, including when the factory is still
+    /// running at that point. Must be called within a tokio runtime with the time driver enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use asyn_object_pool::{BundledPool, PoolError, Resettable};
+    /// use std::time::Duration;
+    ///
+    /// #[derive(Debug)]
+    /// struct Item;
+    ///
+    /// impl Resettable for Item {
+    ///     fn reset(&mut self) {}
+    /// }
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// let pool = BundledPool::new(0, 1, || Item);
+    /// let _item = pool.take_timeout(Duration::from_millis(10)).await.unwrap();
+    ///
+    /// // The only object is checked out and never comes back.
+    /// let err = pool.take_timeout(Duration::from_millis(10)).await.unwrap_err();
+    /// assert_eq!(err, PoolError::Timeout);
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn take_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<BundledPoolItem<T>, PoolError<E>> {
+        tokio::time::timeout(timeout, self.take_async())
+            .await
+            .unwrap_or_else(|_| Err(self.timed_out()))
+    }
+
+    /// Like [`take_async`](Self::take_async), but gives up once `deadline` has passed.
+    ///
+    /// Returns [`PoolError::Timeout`] if no object became available in time, including when the factory is still
+    /// running at that point. Must be called within a tokio runtime with the time driver enabled.
+    #[cfg(feature = "async")]
+    pub async fn take_until(&self, deadline: Instant) -> Result<BundledPoolItem<T>, PoolError<E>> {
+        tokio::time::timeout_at(deadline.into(), self.take_async())
+            .await
+            .unwrap_or_else(|_| Err(self.timed_out()))
+    }
+
     /// Attempts to take an item from the pool without allocating.
     ///
-    /// Returns `None` if no objects are available in the pool.
+    /// # Errors
+    ///
+    /// Returns [`PoolError::Exhausted`] if no objects are available in the pool.
     ///
     /// # Examples
     ///
     /// ```
-    /// use asyn_object_pool::{BundledPool, Resettable};
+    /// use asyn_object_pool::{BundledPool, PoolError, Resettable};
     ///
     /// #[derive(Debug)]
     /// struct Resource;
@@ -193,19 +539,21 @@ impl<T: Resettable> BundledPool<T> {
     ///
     /// // First try_take succeeds
     /// let item = pool.try_take();
-    /// assert!(item.is_some());
+    /// assert!(item.is_ok());
     ///
     /// // Second try_take fails (pool is empty)
     /// let item2 = pool.try_take();
-    /// assert!(item2.is_none());
+    /// assert!(matches!(item2, Err(PoolError::Exhausted)));
     /// ```
-    #[must_use]
     #[inline]
-    pub fn try_take(&self) -> Option<BundledPoolItem<T>> {
-        self.data.items.pop().map(|object| BundledPoolItem {
-            data: Arc::downgrade(&self.data),
-            object: Some(object),
-        })
+    pub fn try_take(&self) -> Result<BundledPoolItem<T>, PoolError<E>> {
+        if self.data.is_closed() {
+            return Err(PoolError::Closed);
+        }
+        self.data
+            .checkout()
+            .map(|entry| self.wrap(entry))
+            .ok_or(PoolError::Exhausted)
     }
 
     /// returns the number of free objects in the pool.
@@ -215,470 +563,3011 @@ impl<T: Resettable> BundledPool<T> {
     }
 
     /// Returns the number of objects currently in use. Does not include objects that have been detached.
-    ///
-    /// This relies on the invariant that each live `BundledPoolItem` holds exactly one `Weak<PoolData<T>>`.
-    /// `Arc::weak_count` therefore equals the number of checked-out items.
     #[inline]
     pub fn used(&self) -> usize {
-        Arc::weak_count(&self.data)
-        // The "invariant" part is the reason this works: it's only correct
-        // because each BundledPoolItem holds exactly one Weak — never zero,
-        // never two. If someone added a second Weak to PoolData somewhere
-        // inside the implementation, used() would overcount without any
-        // compiler error. The comment flags that as a contract future
-        // maintainers must preserve.
-    }
-
-    #[inline]
-    pub fn capacity(&self) -> usize {
-        self.data.items.capacity()
-    }
-}
-
-impl<T: Resettable> Clone for BundledPool<T> {
-    #[inline]
-    fn clone(&self) -> Self {
-        Self {
-            data: Arc::clone(&self.data),
-        }
-    }
-}
-
-// data shared by a `BundledPool`.
-struct PoolData<T> {
-    items: ArrayQueue<T>,
-    create: Box<dyn Fn() -> T + Sync + Send + 'static>,
-}
-
-impl<T: Resettable + Debug> Debug for PoolData<T> {
-    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
-        formatter
-            .debug_struct("PoolData")
-            .field("items", &self.items)
-            .field("create", &"Box<dyn Fn() -> T>")
-            .finish()
+        self.data.counters.checked_out.load(Ordering::Relaxed)
     }
-}
-
-/// an object, checked out from a dynamic pool object.
-#[derive(Debug)]
-pub struct BundledPoolItem<T: Resettable> {
-    data: Weak<PoolData<T>>,
-    object: Option<T>,
-}
 
-impl<T: Resettable> BundledPoolItem<T> {
-    /// Wraps this item in an `Arc` for shared ownership.
+    /// Returns a snapshot of the pool's gauges and event counters.
     ///
-    /// **Limitations when wrapped in `Arc`:**
-    /// - `DerefMut` is unavailable unless you hold the sole reference (`Arc::get_mut`).
-    /// - `detach()` cannot be called because it requires consuming `self` by value.
+    /// # Examples
     ///
-    /// This is mainly useful when you need shared *immutable* access across threads,
-    /// or when `T` uses interior mutability.
-    #[inline]
-    pub fn into_arc(self) -> Arc<Self> {
-        Arc::new(self)
+    /// ```
+    /// use asyn_object_pool::{BundledPool, Resettable};
+    ///
+    /// #[derive(Debug)]
+    /// struct Item;
+    ///
+    /// impl Resettable for Item {
+    ///     fn reset(&mut self) {}
+    /// }
+    ///
+    /// let pool = BundledPool::new(1, 2, || Item);
+    /// let item = pool.take().unwrap();
+    /// let _extra = pool.take().unwrap();
+    /// drop(item);
+    ///
+    /// let stats = pool.stats();
+    /// assert_eq!(stats.idle, 1);
+    /// assert_eq!(stats.live, 2);
+    /// assert_eq!(stats.checked_out, 1);
+    /// assert_eq!(stats.created, 2);
+    /// assert_eq!(stats.resets, 1);
+    /// ```
+    pub fn stats(&self) -> PoolStats {
+        self.data.stats()
     }
 
-    /// Detaches this instance from the pool, returning the inner object.
-    ///
-    /// The detached object will not be returned to the pool when dropped.
+    /// Zeroes the event counters reported by [`stats`](Self::stats) and restarts the high-water mark from the number
+    /// of objects currently checked out. The gauges are not affected.
     ///
     /// # Examples
     ///
     /// ```
     /// use asyn_object_pool::{BundledPool, Resettable};
     ///
-    /// #[derive(Debug, PartialEq)]
-    /// struct Data { value: i32 }
+    /// #[derive(Debug)]
+    /// struct Item;
     ///
-    /// impl Resettable for Data {
-    ///     fn reset(&mut self) { self.value = 0; }
+    /// impl Resettable for Item {
+    ///     fn reset(&mut self) {}
     /// }
     ///
-    /// let pool = BundledPool::new(1, 2, || Data { value: 42 });
-    /// let item = pool.take();
+    /// let pool = BundledPool::new(1, 2, || Item);
+    /// let item1 = pool.take().unwrap(); // hit
+    /// let item2 = pool.take().unwrap(); // miss
+    /// drop((item1, item2));
     ///
-    /// // Detach the object
-    /// let data = item.detach();
-    /// assert_eq!(data.value, 42);
+    /// let stats = pool.stats();
+    /// assert_eq!((stats.hits, stats.misses, stats.high_water), (1, 1, 2));
     ///
-    /// // Pool doesn't get the object back
-    /// assert_eq!(pool.available(), 0);
+    /// pool.reset_stats();
+    /// let stats = pool.stats();
+    /// assert_eq!((stats.hits, stats.misses, stats.high_water), (0, 0, 0));
+    /// assert_eq!(stats.idle, 2);
     /// ```
-    #[inline]
-    pub fn detach(mut self) -> T {
-        self.object
-            .take()
-            .expect("invariant: object is always `some`.")
+    pub fn reset_stats(&self) {
+        self.data.counters.reset();
     }
-}
 
-impl<T: Resettable> AsRef<T> for BundledPoolItem<T> {
+    /// Returns the name given with [`BundledPoolBuilder::name`], `"default"` if none was set.
     #[inline]
-    fn as_ref(&self) -> &T {
-        self.object
-            .as_ref()
-            .expect("invariant: object is always `some`.")
+    pub fn name(&self) -> &str {
+        &self.data.options.name
     }
-}
 
-impl<T: Resettable> AsMut<T> for BundledPoolItem<T> {
     #[inline]
-    fn as_mut(&mut self) -> &mut T {
-        self.object
-            .as_mut()
-            .expect("invariant: object is always `some`.")
+    pub fn capacity(&self) -> usize {
+        self.data.items.capacity()
     }
-}
-
-impl<T: Resettable> Deref for BundledPoolItem<T> {
-    type Target = T;
 
-    #[inline]
-    fn deref(&self) -> &T {
-        self.object
-            .as_ref()
-            .expect("invariant: object is always `some`.")
+    /// Throws away the idle objects that outlived [`idle_timeout`](BundledPoolBuilder::idle_timeout) or
+    /// [`max_lifetime`](BundledPoolBuilder::max_lifetime), returning how many there were.
+    ///
+    /// Expired objects are also weeded out at checkout, so calling this is only needed to release them early. The
+    /// other idle objects are briefly taken out of the pool while they are looked at.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use asyn_object_pool::{BundledPool, Resettable};
+    /// use std::time::Duration;
+    ///
+    /// #[derive(Debug)]
+    /// struct Connection;
+    ///
+    /// impl Resettable for Connection {
+    ///     fn reset(&mut self) {}
+    /// }
+    ///
+    /// let pool = BundledPool::builder()
+    ///     .min_idle(2)
+    ///     .max_idle(2)
+    ///     .idle_timeout(Duration::from_millis(10))
+    ///     .factory(|| Connection)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// std::thread::sleep(Duration::from_millis(20));
+    /// assert_eq!(pool.evict_expired(), 2);
+    /// assert_eq!(pool.available(), 0);
+    /// ```
+    pub fn evict_expired(&self) -> usize {
+        self.data.evict_expired()
     }
-}
 
-impl<T: Resettable> DerefMut for BundledPoolItem<T> {
-    #[inline]
-    fn deref_mut(&mut self) -> &mut T {
-        self.object
-            .as_mut()
-            .expect("invariant: object is always `some`.")
+    /// Starts a task on the current tokio runtime that keeps the pool in shape every `interval`: it throws away
+    /// expired idle objects, as [`evict_expired`](Self::evict_expired) does, and tops the idle objects back up to
+    /// `min_idle` (`initial_capacity` for the positional constructors) as far as the live-object cap allows.
+    ///
+    /// The first round runs right away. The task stops once the last handle to the pool is dropped; starting it again
+    /// replaces the running one. Objects the factory fails to create are retried on the next round.
+    ///
+    /// Needs the `rt` feature, enabled by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero, or if called outside of a tokio runtime.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use asyn_object_pool::{BundledPool, Resettable};
+    /// use std::time::Duration;
+    ///
+    /// #[derive(Debug)]
+    /// struct Connection;
+    ///
+    /// impl Resettable for Connection {
+    ///     fn reset(&mut self) {}
+    /// }
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let pool = BundledPool::new(2, 8, || Connection);
+    /// pool.start_maintenance(Duration::from_millis(10));
+    ///
+    /// // A burst takes the warm objects, which are detached and never come back
+    /// pool.take().unwrap().detach();
+    /// pool.take().unwrap().detach();
+    /// assert_eq!(pool.available(), 0);
+    ///
+    /// tokio::time::sleep(Duration::from_millis(50)).await;
+    /// assert_eq!(pool.available(), 2);
+    /// # }
+    /// ```
+    #[cfg(feature = "rt")]
+    pub fn start_maintenance(&self, interval: Duration)
+    where
+        T: Send + 'static,
+        E: 'static,
+    {
+        assert!(
+            !interval.is_zero(),
+            "the maintenance interval must not be zero"
+        );
+        let task = tokio::spawn(maintain(
+            Arc::downgrade(&self.data),
+            Arc::clone(&self.factory),
+            interval,
+        ));
+        let previous = self
+            .data
+            .maintenance
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .replace(task.abort_handle());
+        if let Some(previous) = previous {
+            previous.abort();
+        }
+    }
+
+    /// Shuts the pool down for every handle to it.
+    ///
+    /// From then on [`take`](Self::take), [`try_take`](Self::try_take) and [`take_async`](Self::take_async) fail with
+    /// [`PoolError::Closed`], including the calls already waiting or creating an object, which is then thrown away.
+    /// The idle objects are thrown away right away, as is every checked-out object when it comes back, and the
+    /// maintenance task is stopped. Closing a closed pool does nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use asyn_object_pool::{BundledPool, PoolError, Resettable};
+    ///
+    /// #[derive(Debug)]
+    /// struct Connection;
+    ///
+    /// impl Resettable for Connection {
+    ///     fn reset(&mut self) {}
+    /// }
+    ///
+    /// let pool = BundledPool::new(2, 4, || Connection);
+    /// let conn = pool.take().unwrap();
+    ///
+    /// pool.close();
+    /// assert!(pool.is_closed());
+    /// assert_eq!(pool.available(), 0);
+    /// assert!(matches!(pool.take(), Err(PoolError::Closed)));
+    ///
+    /// // Not kept once it comes back
+    /// drop(conn);
+    /// assert_eq!(pool.stats().live, 0);
+    /// ```
+    pub fn close(&self) {
+        self.data.close();
+    }
+
+    /// Returns `true` once [`close`](Self::close) has been called on any handle to the pool.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.data.is_closed()
+    }
+
+    /// Waits until the pool is closed and every object it handed out has come back and been thrown away.
+    ///
+    /// Objects that are detached count as gone. Resolves right away if that is already the case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use asyn_object_pool::{BundledPool, Resettable};
+    ///
+    /// #[derive(Debug)]
+    /// struct Connection;
+    ///
+    /// impl Resettable for Connection {
+    ///     fn reset(&mut self) {}
+    /// }
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let pool = BundledPool::new(1, 4, || Connection);
+    /// let conn = pool.take().unwrap();
+    /// tokio::spawn(async move {
+    ///     // Still in use for a while
+    ///     drop(conn);
+    /// });
+    ///
+    /// pool.close();
+    /// pool.closed().await;
+    /// assert_eq!(pool.stats().live, 0);
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn closed(&self) {
+        loop {
+            let settled = self.data.settled.notified();
+            tokio::pin!(settled);
+            settled.as_mut().enable();
+            if self.data.is_closed() && self.data.live.load(Ordering::Acquire) == 0 {
+                return;
+            }
+            settled.await;
+        }
+    }
+
+    /// Waits up to `timeout` for every checked-out object to come back, returning `false` if some were still out by
+    /// then. An object counts as back once it is in the pool again or thrown away, after its reset, including one
+    /// running in the background with [`async_reset`](BundledPoolBuilder::async_reset).
+    ///
+    /// Works whether or not the pool is closed; objects checked out while waiting are waited for too. Must be called
+    /// within a tokio runtime with the time driver enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use asyn_object_pool::{BundledPool, Resettable};
+    /// use std::time::Duration;
+    ///
+    /// #[derive(Debug)]
+    /// struct Connection;
+    ///
+    /// impl Resettable for Connection {
+    ///     fn reset(&mut self) {}
+    /// }
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// let pool = BundledPool::new(1, 4, || Connection);
+    /// let conn = pool.take().unwrap();
+    ///
+    /// pool.close();
+    /// assert!(!pool.wait_idle(Duration::from_millis(10)).await);
+    /// drop(conn);
+    /// assert!(pool.wait_idle(Duration::from_millis(10)).await);
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn wait_idle(&self, timeout: Duration) -> bool {
+        let idle = async {
+            loop {
+                let settled = self.data.settled.notified();
+                tokio::pin!(settled);
+                settled.as_mut().enable();
+                if self.data.counters.checked_out.load(Ordering::Acquire) == 0
+                    && self.data.resetting.load(Ordering::Acquire) == 0
+                {
+                    return;
+                }
+                settled.await;
+            }
+        };
+        tokio::time::timeout(timeout, idle).await.is_ok()
+    }
+
+    pub(crate) fn sampler(&self) -> Weak<dyn Sampled>
+    where
+        T: Send + 'static,
+    {
+        Arc::downgrade(&self.data) as Weak<dyn Sampled>
+    }
+
+    #[cfg(feature = "async")]
+    #[inline]
+    fn timed_out(&self) -> PoolError<E> {
+        self.data.telemetry.timed_out();
+        PoolError::Timeout
+    }
+
+    /// Hands out an object fresh from the factory, unless the pool was closed while it was being created.
+    fn wrap_created(&self, object: T) -> Result<BundledPoolItem<T>, PoolError<E>> {
+        let entry = self.data.created(object);
+        if self.data.is_closed() {
+            self.data
+                .destroy(entry.object, entry.meta.id, DiscardReason::Closed);
+            self.data.publish_gauges();
+            return Err(PoolError::Closed);
+        }
+        Ok(self.wrap(entry))
+    }
+
+    #[inline]
+    fn wrap(&self, entry: Entry<T>) -> BundledPoolItem<T> {
+        self.data.counters.check_out();
+        self.data.publish_gauges();
+        self.data.telemetry.checked_out(entry.meta.id);
+        BundledPoolItem {
+            data: Arc::downgrade(&self.data),
+            on_destroy: self.data.options.on_destroy.clone(),
+            object: Some(entry.object),
+            meta: ObjectMeta {
+                uses: entry.meta.uses + 1,
+                ..entry.meta
+            },
+            checked_out_at: Instant::now(),
+            broken: false,
+        }
     }
 }
 
-impl<T: Resettable> Drop for BundledPoolItem<T> {
+impl<T: Resettable, E> Clone for BundledPool<T, E> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            data: Arc::clone(&self.data),
+            factory: Arc::clone(&self.factory),
+        }
+    }
+}
+
+// what the pool knows about an object besides its value; travels with it while checked out.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ObjectMeta {
+    // unique within the pool, in creation order.
+    pub(crate) id: u64,
+    pub(crate) created_at: Instant,
+    // number of checkouts so far.
+    pub(crate) uses: u64,
+}
+
+// an idle object.
+#[derive(Debug)]
+pub(crate) struct Entry<T> {
+    pub(crate) object: T,
+    pub(crate) meta: ObjectMeta,
+    // returned without a reset, which is then due at checkout.
+    pub(crate) dirty: bool,
+    // when the object was created or last returned.
+    pub(crate) idle_since: Instant,
+}
+
+impl<T> Entry<T> {
+    #[inline]
+    pub(crate) fn new(id: u64, object: T) -> Self {
+        Self {
+            object,
+            meta: ObjectMeta {
+                id,
+                created_at: Instant::now(),
+                uses: 0,
+            },
+            dirty: false,
+            idle_since: Instant::now(),
+        }
+    }
+}
+
+// data shared by a `BundledPool`.
+pub(crate) struct PoolData<T> {
+    items: ArrayQueue<Entry<T>>,
+    // objects created by the pool and not yet thrown away or detached (idle plus checked out).
+    live: AtomicUsize,
+    next_id: AtomicU64,
+    max_live: usize,
+    // objects the maintenance task keeps idle.
+    #[cfg(feature = "rt")]
+    min_idle: usize,
+    // notified once per released object so that a single `take_async` waiter can retry.
+    #[cfg(feature = "async")]
+    returned: Notify,
+    // objects being reset in the background after their item was dropped.
+    #[cfg(feature = "async")]
+    resetting: AtomicUsize,
+    // notified whenever the last checked-out item or background reset is done, or the last live object goes away.
+    #[cfg(feature = "async")]
+    settled: Notify,
+    closed: AtomicBool,
+    // the maintenance task, stopped when the pool is closed or dropped.
+    #[cfg(feature = "rt")]
+    maintenance: Mutex<Option<AbortHandle>>,
+    counters: PoolCounters,
+    telemetry: Telemetry,
+    options: PoolOptions<T>,
+}
+
+impl<T> PoolData<T> {
+    fn stats(&self) -> PoolStats {
+        self.counters
+            .snapshot(self.items.len(), self.live.load(Ordering::Acquire))
+    }
+
+    /// Counts an object created on demand and gives it the next id.
+    #[inline]
+    fn created(&self, object: T) -> Entry<T> {
+        self.new_entry(object, true)
+    }
+
+    /// Gives a new object the next id, counting it as created `on_demand` for a checkout or not.
+    #[inline]
+    fn new_entry(&self, object: T, on_demand: bool) -> Entry<T> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.counters.created(on_demand);
+        self.telemetry.created(id);
+        Entry::new(id, object)
+    }
+
+    /// Ends a checkout that lasted `held`.
+    #[inline]
+    fn check_in(&self, held: Duration) {
+        if self.counters.checked_out.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.settle();
+        }
+        self.counters.hold_time.record(held);
+        self.telemetry.held(held);
+    }
+
+    /// Ends a background reset, once its object was put back or thrown away.
+    #[cfg(feature = "rt")]
+    #[inline]
+    fn reset_done(&self) {
+        if self.resetting.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.settle();
+        }
+    }
+
+    /// Records how long `take_async` took to hand out an object.
+    #[cfg(feature = "async")]
+    #[inline]
+    fn waited(&self, elapsed: Duration) {
+        self.counters.wait_time.record(elapsed);
+        self.telemetry.waited(elapsed);
+    }
+
+    /// Wakes one `take_async` waiting for an object or a free slot.
+    #[inline]
+    fn wake_one(&self) {
+        #[cfg(feature = "async")]
+        self.returned.notify_one();
+    }
+
+    /// Wakes the callers of `closed` and `wait_idle` to check again.
+    #[inline]
+    fn settle(&self) {
+        #[cfg(feature = "async")]
+        self.settled.notify_waiters();
+    }
+
+    /// Reports the idle and live gauges to the `metrics` recorder, if the feature is enabled.
+    #[inline]
+    fn publish_gauges(&self) {
+        #[cfg(feature = "metrics")]
+        self.telemetry
+            .gauges(self.items.len(), self.live.load(Ordering::Relaxed));
+    }
+
+    /// Claims a slot for a new object, unless `max_live` objects are already alive.
+    #[cfg(feature = "async")]
+    #[inline]
+    fn try_reserve(&self) -> bool {
+        self.live
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |live| {
+                (live < self.max_live).then_some(live + 1)
+            })
+            .is_ok()
+    }
+
+    /// Puts a returned object back into the pool, or throws it away if the pool is full. A `dirty` object has not
+    /// been reset yet.
+    fn give_back(&self, mut object: T, meta: ObjectMeta, dirty: bool) {
+        // A dirty object gets the hook after its reset, at checkout
+        if !dirty
+            && let Some(on_return) = &self.options.on_return
+            && let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| on_return(&mut object)))
+        {
+            // Left half-way through the hook, so it cannot be reused
+            self.destroy(object, meta.id, DiscardReason::Panic);
+            self.publish_gauges();
+            // Unwinding out of a destructor that runs during another panic would abort the process
+            if !thread::panicking() {
+                panic::resume_unwind(payload);
+            }
+            return;
+        }
+        self.telemetry.returned(meta.id);
+        let entry = Entry {
+            object,
+            meta,
+            dirty,
+            idle_since: Instant::now(),
+        };
+        // If the pool is full, we destroy the object and free its slot
+        if let Err(entry) = self.push_idle(entry) {
+            self.destroy(entry.object, entry.meta.id, DiscardReason::Full);
+        }
+    }
+
+    /// Adds an idle object and wakes one waiter, or hands the object back if the pool is full. In a closed pool the
+    /// object is thrown away instead.
+    fn push_idle(&self, entry: Entry<T>) -> Result<(), Entry<T>> {
+        if self.is_closed() {
+            self.destroy(entry.object, entry.meta.id, DiscardReason::Closed);
+            return Ok(());
+        }
+        self.items.push(entry)?;
+        self.wake_one();
+        // `close` may have drained the pool between the check and the push
+        if self.is_closed() {
+            self.drain();
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    /// Closes the pool and throws its idle objects away. Returns `false` if it was already closed.
+    fn close(&self) -> bool {
+        if self.closed.swap(true, Ordering::AcqRel) {
+            return false;
+        }
+        self.stop_maintenance();
+        self.drain();
+        // Waiters find the pool closed when they look again
+        #[cfg(feature = "async")]
+        self.returned.notify_waiters();
+        self.settle();
+        true
+    }
+
+    /// Stops the maintenance task, if one is running.
+    fn stop_maintenance(&self) {
+        #[cfg(feature = "rt")]
+        {
+            let maintenance = self
+                .maintenance
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .take();
+            if let Some(maintenance) = maintenance {
+                maintenance.abort();
+            }
+        }
+    }
+
+    /// Throws away every idle object of a closed pool.
+    fn drain(&self) {
+        while let Some(entry) = self.items.pop() {
+            self.destroy(entry.object, entry.meta.id, DiscardReason::Closed);
+        }
+        self.publish_gauges();
+    }
+
+    /// Counts an object thrown away for `reason` and frees its slot. The object itself, if any is left, is dropped by
+    /// the caller without going through the `on_destroy` hook.
+    #[inline]
+    fn discard(&self, id: u64, reason: DiscardReason) {
+        self.counters.discarded(reason);
+        self.telemetry.discarded(id, reason);
+        self.release_slot();
+    }
+
+    /// Hands an object that leaves the pool for good to the `on_destroy` hook, then counts it as thrown away for
+    /// `reason`. The slot is freed even if the hook panics.
+    fn destroy(&self, object: T, id: u64, reason: DiscardReason) {
+        self.run_on_destroy(object);
+        self.discard(id, reason);
+    }
+
+    /// Runs the `on_destroy` hook, if any, swallowing its panics: they would otherwise leak the object's slot, or
+    /// abort the process when the hook runs during unwinding.
+    fn run_on_destroy(&self, object: T) {
+        run_destroy_hook(self.options.on_destroy.as_ref(), object);
+    }
+
+    /// Whether `max_lifetime` has passed since the object was created.
+    #[inline]
+    fn outlived(&self, meta: &ObjectMeta, now: Instant) -> bool {
+        self.options.max_lifetime.is_some_and(|max_lifetime| {
+            now.saturating_duration_since(meta.created_at) >= max_lifetime
+        })
+    }
+
+    /// Whether an idle object has outlived `idle_timeout` or `max_lifetime`.
+    #[inline]
+    fn expired(&self, entry: &Entry<T>, now: Instant) -> bool {
+        self.outlived(&entry.meta, now)
+            || self.options.idle_timeout.is_some_and(|idle_timeout| {
+                now.saturating_duration_since(entry.idle_since) >= idle_timeout
+            })
+    }
+
+    /// Throws away the expired idle objects, putting the others back in the pool. Returns how many were thrown away.
+    fn evict_expired(&self) -> usize {
+        if self.options.idle_timeout.is_none() && self.options.max_lifetime.is_none() {
+            return 0;
+        }
+        let now = Instant::now();
+        let mut evicted = 0;
+        // Each idle object is looked at once; objects returned meanwhile are left for the next sweep
+        for _ in 0..self.items.len() {
+            let Some(entry) = self.items.pop() else {
+                break;
+            };
+            if self.expired(&entry, now) {
+                self.destroy(entry.object, entry.meta.id, DiscardReason::Expired);
+                evicted += 1;
+            } else if let Err(entry) = self.push_idle(entry) {
+                // Filled up by objects returned while this one was out
+                self.destroy(entry.object, entry.meta.id, DiscardReason::Full);
+            }
+        }
+        self.publish_gauges();
+        evicted
+    }
+
+    /// Claims a slot for an idle object if fewer than `min_idle` are idle and `max_live` are not alive yet.
+    #[cfg(feature = "rt")]
+    fn reserve_idle(&self) -> bool {
+        !self.is_closed() && self.items.len() < self.min_idle && self.try_reserve()
+    }
+
+    /// Adds an object created for a slot claimed with [`reserve_idle`](Self::reserve_idle) to the idle ones. Returns
+    /// false, throwing the object away, if the pool filled up in the meantime.
+    #[cfg(feature = "rt")]
+    fn add_idle(&self, object: T) -> bool {
+        let entry = self.new_entry(object, false);
+        let added = match self.push_idle(entry) {
+            Ok(()) => true,
+            Err(entry) => {
+                self.destroy(entry.object, entry.meta.id, DiscardReason::Full);
+                false
+            }
+        };
+        self.publish_gauges();
+        added
+    }
+
+    /// Gives up the slot of an object that will never come back, waking one waiter.
+    #[inline]
+    fn release_slot(&self) {
+        if self.live.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.settle();
+        }
+        self.wake_one();
+    }
+}
+
+/// Runs the maintenance of a pool every `interval`, until the pool is dropped.
+#[cfg(feature = "rt")]
+async fn maintain<T, E>(pool: Weak<PoolData<T>>, factory: Arc<Factory<T, E>>, interval: Duration)
+where
+    T: Send + 'static,
+{
+    let mut ticks = tokio::time::interval(interval);
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        ticks.tick().await;
+        match pool.upgrade() {
+            Some(pool) if !pool.is_closed() => pool.evict_expired(),
+            _ => return,
+        };
+        // Tops the idle objects up to `min_idle`, stopping at the first factory error. Only the weak reference is
+        // held while the factory runs, so dropping the last handle frees the pool even if it never finishes.
+        while let Some(slot) = WeakSlotGuard::reserve(&pool) {
+            let Ok(object) = factory.create().await else {
+                break;
+            };
+            let Some(pool) = slot.keep() else {
+                return;
+            };
+            if !pool.add_idle(object) {
+                break;
+            }
+        }
+    }
+}
+
+/// Runs `on_destroy`, if any, on an object, swallowing its panics like [`PoolData::run_on_destroy`].
+fn run_destroy_hook<T>(on_destroy: Option<&DestroyHook<T>>, object: T) {
+    if let Some(on_destroy) = on_destroy {
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| on_destroy(object)));
+    }
+}
+
+// starts the reset of a returned object in the background; hands the object back if that is not possible.
+pub(crate) type BackgroundReset<T> = fn(&Arc<PoolData<T>>, T, ObjectMeta) -> Result<(), T>;
+
+/// Resets a returned object with `AsyncResettable::reset_async` in a task on the current tokio runtime, then gives it
+/// back to the pool. Fails, returning the object, outside a runtime.
+#[cfg(feature = "rt")]
+pub(crate) fn spawn_reset<T>(pool: &Arc<PoolData<T>>, object: T, meta: ObjectMeta) -> Result<(), T>
+where
+    T: AsyncResettable + Send + 'static,
+{
+    let Ok(runtime) = Handle::try_current() else {
+        return Err(object);
+    };
+    pool.resetting.fetch_add(1, Ordering::AcqRel);
+    let pending = PendingReset {
+        pool: Arc::downgrade(pool),
+        on_destroy: pool.options.on_destroy.clone(),
+        meta,
+        done: false,
+    };
+    runtime.spawn(async move {
+        let mut object = object;
+        let outcome = object.reset_async().await;
+        pending.finish(object, outcome);
+    });
+    Ok(())
+}
+
+// an object being reset in the background. If the reset never completes, because it panicked or its task was
+// cancelled, the object is counted as thrown away so that its slot is not lost. Either way the reset is only done
+// once the guard is dropped.
+#[cfg(feature = "rt")]
+struct PendingReset<T> {
+    pool: Weak<PoolData<T>>,
+    // for an object whose reset outlives the pool.
+    on_destroy: Option<DestroyHook<T>>,
+    meta: ObjectMeta,
+    done: bool,
+}
+
+#[cfg(feature = "rt")]
+impl<T> PendingReset<T> {
+    fn finish(mut self, object: T, outcome: ResetOutcome) {
+        self.done = true;
+        let Some(pool) = self.pool.upgrade() else {
+            run_destroy_hook(self.on_destroy.as_ref(), object);
+            return;
+        };
+        pool.counters.resets.fetch_add(1, Ordering::Relaxed);
+        match outcome {
+            ResetOutcome::Keep => pool.give_back(object, self.meta, false),
+            ResetOutcome::Discard => pool.destroy(object, self.meta.id, DiscardReason::Reset),
+        }
+        pool.publish_gauges();
+    }
+}
+
+#[cfg(feature = "rt")]
+impl<T> Drop for PendingReset<T> {
     fn drop(&mut self) {
-        if let Some(mut object) = self.object.take() {
-            object.reset();
-            if let Some(pool) = self.data.upgrade() {
-                // Ignore the result - if the pool is full, we just drop the object
-                let _ = pool.items.push(object);
+        let Some(pool) = self.pool.upgrade() else {
+            return;
+        };
+        if !self.done {
+            let reason = if thread::panicking() {
+                DiscardReason::Panic
+            } else {
+                DiscardReason::Reset
+            };
+            pool.discard(self.meta.id, reason);
+            pool.publish_gauges();
+        }
+        pool.reset_done();
+    }
+}
+
+impl<T: Resettable> PoolData<T> {
+    /// Pops an idle object and counts the checkout as a hit. Objects returned dirty are reset first; objects that
+    /// expired, cannot be reused or fail validation are thrown away in favour of the next one.
+    fn checkout(&self) -> Option<Entry<T>> {
+        loop {
+            let mut entry = self.items.pop()?;
+            if self.expired(&entry, Instant::now()) {
+                self.destroy(entry.object, entry.meta.id, DiscardReason::Expired);
+                self.publish_gauges();
+                continue;
+            }
+            // A panicking reset, hook or validator must not leave the object's slot taken either
+            match panic::catch_unwind(AssertUnwindSafe(|| self.prepare(&mut entry))) {
+                Ok(Ok(())) => {}
+                Ok(Err(reason)) => {
+                    self.destroy(entry.object, entry.meta.id, reason);
+                    self.publish_gauges();
+                    continue;
+                }
+                Err(payload) => {
+                    self.discard(entry.meta.id, DiscardReason::Panic);
+                    self.publish_gauges();
+                    drop(entry);
+                    panic::resume_unwind(payload);
+                }
+            }
+            self.counters.hits.fetch_add(1, Ordering::Relaxed);
+            return Some(entry);
+        }
+    }
+
+    /// Readies an idle object for checkout: resets it if it was returned dirty, then validates it. Returns why it has
+    /// to be thrown away instead, if it does.
+    fn prepare(&self, entry: &mut Entry<T>) -> Result<(), DiscardReason> {
+        if entry.dirty {
+            let outcome = {
+                let _span = self.telemetry.reset_span(entry.meta.id);
+                entry.object.try_reset()
+            };
+            self.counters.resets.fetch_add(1, Ordering::Relaxed);
+            if outcome == ResetOutcome::Discard {
+                return Err(DiscardReason::Reset);
             }
+            entry.dirty = false;
+            if let Some(on_return) = &self.options.on_return {
+                on_return(&mut entry.object);
+            }
+        }
+        if let Some(validate) = &self.options.validate
+            && !validate(&entry.object)
+        {
+            return Err(DiscardReason::Invalid);
         }
+        Ok(())
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::sync::Arc;
-    use std::thread;
+impl<T: Send> Sampled for PoolData<T> {
+    fn sample(&self) -> Sample {
+        Sample {
+            name: self.options.name.clone(),
+            capacity: self.items.capacity(),
+            stats: self.stats(),
+            wait_counts: self.counters.wait_time.counts(),
+            wait_sum: self.counters.wait_time.sum(),
+        }
+    }
+}
 
-    #[derive(Debug, PartialEq)]
-    struct TestObj {
-        value: usize,
+// a slot reserved in `PoolData::live` for an object that is being created; given back unless kept.
+struct SlotGuard<'a, T>(&'a PoolData<T>);
+
+impl<T> SlotGuard<'_, T> {
+    #[inline]
+    fn keep(self) {
+        std::mem::forget(self);
+    }
+}
+
+impl<T> Drop for SlotGuard<'_, T> {
+    fn drop(&mut self) {
+        self.0.release_slot();
+    }
+}
+
+// like `SlotGuard`, for a task that must not keep the pool alive while the object is created.
+#[cfg(feature = "rt")]
+struct WeakSlotGuard<T>(Weak<PoolData<T>>);
+
+#[cfg(feature = "rt")]
+impl<T> WeakSlotGuard<T> {
+    fn reserve(pool: &Weak<PoolData<T>>) -> Option<Self> {
+        pool.upgrade()
+            .is_some_and(|pool| pool.reserve_idle())
+            .then(|| Self(pool.clone()))
+    }
+
+    // the pool to add the object to, unless it was dropped in the meantime.
+    fn keep(self) -> Option<Arc<PoolData<T>>> {
+        let pool = self.0.upgrade();
+        std::mem::forget(self);
+        pool
+    }
+}
+
+#[cfg(feature = "rt")]
+impl<T> Drop for WeakSlotGuard<T> {
+    fn drop(&mut self) {
+        if let Some(pool) = self.0.upgrade() {
+            pool.release_slot();
+        }
+    }
+}
+
+// ends a checkout when dropped, so that the item only counts as back once its object was put back, handed to a
+// background reset or thrown away, even if that panicked.
+struct CheckIn<'a, T> {
+    pool: &'a PoolData<T>,
+    held: Duration,
+}
+
+impl<T> Drop for CheckIn<'_, T> {
+    fn drop(&mut self) {
+        self.pool.check_in(self.held);
+    }
+}
+
+impl<T> Drop for PoolData<T> {
+    fn drop(&mut self) {
+        self.stop_maintenance();
+        // Idle objects are destroyed too; those still checked out are when they come back
+        while let Some(entry) = self.items.pop() {
+            self.run_on_destroy(entry.object);
+        }
+    }
+}
+
+impl<T: Resettable + Debug> Debug for PoolData<T> {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        formatter
+            .debug_struct("PoolData")
+            .field("items", &self.items)
+            .field("live", &self.live)
+            .field("max_live", &self.max_live)
+            .finish()
+    }
+}
+
+/// an object, checked out from a dynamic pool object.
+pub struct BundledPoolItem<T: Resettable> {
+    data: Weak<PoolData<T>>,
+    // the pool's `on_destroy` hook, for an object returned after the pool was dropped.
+    on_destroy: Option<DestroyHook<T>>,
+    object: Option<T>,
+    meta: ObjectMeta,
+    checked_out_at: Instant,
+    // set by `mark_broken`: the object is destroyed rather than returned on drop.
+    broken: bool,
+}
+
+impl<T: Resettable> BundledPoolItem<T> {
+    /// Returns the id the pool gave this object when creating it, which is also reported in traces.
+    ///
+    /// Ids are unique within a pool and follow creation order, starting at 0 with the pre-created objects.
+    #[inline]
+    pub fn id(&self) -> u64 {
+        self.meta.id
+    }
+
+    /// Returns how many times the object has been checked out, this checkout included.
+    #[inline]
+    pub fn uses(&self) -> u64 {
+        self.meta.uses
+    }
+
+    /// Wraps this item in an `Arc` for shared ownership.
+    ///
+    /// **Limitations when wrapped in `Arc`:**
+    /// - `DerefMut` is unavailable unless you hold the sole reference (`Arc::get_mut`).
+    /// - `detach()` cannot be called because it requires consuming `self` by value.
+    ///
+    /// This is mainly useful when you need shared *immutable* access across threads,
+    /// or when `T` uses interior mutability.
+    #[inline]
+    pub fn into_arc(self) -> Arc<Self> {
+        Arc::new(self)
+    }
+
+    /// Detaches this instance from the pool, returning the inner object.
+    ///
+    /// The detached object will not be returned to the pool when dropped. Its slot counts as free again, so a
+    /// waiting [`BundledPool::take_async`] may create a replacement.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use asyn_object_pool::{BundledPool, Resettable};
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct Data { value: i32 }
+    ///
+    /// impl Resettable for Data {
+    ///     fn reset(&mut self) { self.value = 0; }
+    /// }
+    ///
+    /// let pool = BundledPool::new(1, 2, || Data { value: 42 });
+    /// let item = pool.take().unwrap();
+    ///
+    /// // Detach the object
+    /// let data = item.detach();
+    /// assert_eq!(data.value, 42);
+    ///
+    /// // Pool doesn't get the object back
+    /// assert_eq!(pool.available(), 0);
+    /// ```
+    #[inline]
+    pub fn detach(mut self) -> T {
+        let object = self
+            .object
+            .take()
+            .expect("invariant: object is always `some`.");
+        if let Some(pool) = self.data.upgrade() {
+            pool.check_in(self.checked_out_at.elapsed());
+            pool.counters.detached.fetch_add(1, Ordering::Relaxed);
+            pool.telemetry.detached(self.meta.id);
+            pool.release_slot();
+            pool.publish_gauges();
+        }
+        object
+    }
+
+    /// Destroys the object instead of returning it to the pool, e.g. after it failed in a way that leaves it
+    /// unusable.
+    ///
+    /// The object is handed to the [`on_destroy`](BundledPoolBuilder::on_destroy) hook, if any, and its slot counts
+    /// as free again, so a waiting [`BundledPool::take_async`] may create a replacement.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use asyn_object_pool::{BundledPool, Resettable};
+    ///
+    /// #[derive(Debug)]
+    /// struct Connection { open: bool }
+    ///
+    /// impl Resettable for Connection {
+    ///     fn reset(&mut self) {}
+    /// }
+    ///
+    /// let pool = BundledPool::builder()
+    ///     .max_idle(2)
+    ///     .factory(|| Connection { open: true })
+    ///     .on_destroy(|connection| println!("closing {:?}", connection))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let mut connection = pool.take().unwrap();
+    /// connection.open = false;
+    /// connection.discard();
+    ///
+    /// assert_eq!(pool.available(), 0);
+    /// assert_eq!(pool.stats().discarded_broken, 1);
+    /// ```
+    #[inline]
+    pub fn discard(mut self) {
+        self.mark_broken();
+    }
+
+    /// Like [`discard`](Self::discard), but destroys the object when the item is dropped rather than now.
+    ///
+    /// Useful where the item cannot be given up yet, for instance while it is still borrowed.
+    #[inline]
+    pub fn mark_broken(&mut self) {
+        self.broken = true;
+    }
+}
+
+impl<T: Resettable> AsRef<T> for BundledPoolItem<T> {
+    #[inline]
+    fn as_ref(&self) -> &T {
+        self.object
+            .as_ref()
+            .expect("invariant: object is always `some`.")
+    }
+}
+
+impl<T: Resettable> AsMut<T> for BundledPoolItem<T> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut T {
+        self.object
+            .as_mut()
+            .expect("invariant: object is always `some`.")
+    }
+}
+
+impl<T: Resettable> Deref for BundledPoolItem<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        self.object
+            .as_ref()
+            .expect("invariant: object is always `some`.")
+    }
+}
+
+impl<T: Resettable> DerefMut for BundledPoolItem<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        self.object
+            .as_mut()
+            .expect("invariant: object is always `some`.")
+    }
+}
+
+impl<T: Resettable + Debug> Debug for BundledPoolItem<T> {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        formatter
+            .debug_struct("BundledPoolItem")
+            .field("data", &self.data)
+            .field("object", &self.object)
+            .field("meta", &self.meta)
+            .field("checked_out_at", &self.checked_out_at)
+            .field("broken", &self.broken)
+            .finish()
+    }
+}
+
+impl<T: Resettable> Drop for BundledPoolItem<T> {
+    fn drop(&mut self) {
+        let Some(mut object) = self.object.take() else {
+            return;
+        };
+        // Once the pool is gone the object has nowhere to go, so it is destroyed without a reset
+        let Some(pool) = self.data.upgrade() else {
+            run_destroy_hook(self.on_destroy.as_ref(), object);
+            return;
+        };
+        let _check_in = CheckIn {
+            pool: &pool,
+            held: self.checked_out_at.elapsed(),
+        };
+        if self.broken {
+            pool.destroy(object, self.meta.id, DiscardReason::Broken);
+            pool.publish_gauges();
+            return;
+        }
+        let worn_out = pool
+            .options
+            .max_uses
+            .is_some_and(|max_uses| self.meta.uses >= max_uses);
+        if worn_out {
+            pool.destroy(object, self.meta.id, DiscardReason::MaxUses);
+            pool.publish_gauges();
+            return;
+        }
+        if pool.outlived(&self.meta, Instant::now()) {
+            pool.destroy(object, self.meta.id, DiscardReason::Expired);
+            pool.publish_gauges();
+            return;
+        }
+        // An object dropped by a panicking thread goes to the recovery hook, if there is one
+        let on_panic = pool
+            .options
+            .on_panic
+            .as_ref()
+            .filter(|_| thread::panicking());
+        if on_panic.is_none() {
+            if pool.options.lazy_reset {
+                pool.give_back(object, self.meta, true);
+                pool.publish_gauges();
+                return;
+            }
+            if let Some(async_reset) = pool.options.async_reset {
+                match async_reset(&pool, object, self.meta) {
+                    Ok(()) => return,
+                    // Not on a runtime: reset it here instead
+                    Err(returned) => object = returned,
+                }
+            }
+        }
+        let outcome = {
+            let _span = pool.telemetry.reset_span(self.meta.id);
+            // A panicking reset must not leave the object's slot taken, so catch it and throw the object away
+            panic::catch_unwind(AssertUnwindSafe(|| match on_panic {
+                Some(on_panic) => on_panic(&mut object),
+                None => object.try_reset(),
+            }))
+        };
+        match outcome {
+            Ok(outcome) => {
+                pool.counters.resets.fetch_add(1, Ordering::Relaxed);
+                match (outcome, on_panic.is_some()) {
+                    (ResetOutcome::Keep, _) => pool.give_back(object, self.meta, false),
+                    (ResetOutcome::Discard, false) => {
+                        pool.destroy(object, self.meta.id, DiscardReason::Reset)
+                    }
+                    (ResetOutcome::Discard, true) => {
+                        pool.destroy(object, self.meta.id, DiscardReason::Panic)
+                    }
+                }
+                pool.publish_gauges();
+            }
+            Err(payload) => {
+                pool.discard(self.meta.id, DiscardReason::Panic);
+                pool.publish_gauges();
+                drop(object);
+                // Unwinding out of a destructor that runs during another panic would abort the process
+                if !thread::panicking() {
+                    panic::resume_unwind(payload);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicBool;
+    use std::thread;
+
+    #[derive(Debug, PartialEq)]
+    struct TestObj {
+        value: usize,
+    }
+
+    impl Resettable for TestObj {
+        fn reset(&mut self) {
+            self.value = 0;
+        }
+    }
+
+    fn make_test_obj(val: usize) -> TestObj {
+        TestObj { value: val }
+    }
+
+    #[test]
+    fn test_pool_creation_and_take() {
+        let pool = BundledPool::new(2, 4, move || make_test_obj(42));
+        assert_eq!(pool.available(), 2);
+
+        let item = pool.take().unwrap();
+        assert_eq!(item.value, 42);
+        assert_eq!(pool.available(), 1);
+    }
+
+    #[test]
+    fn test_item_ids_follow_creation_order() {
+        let pool = BundledPool::new(2, 4, move || make_test_obj(1));
+        let item0 = pool.take().unwrap();
+        let item1 = pool.take().unwrap();
+        let item2 = pool.take().unwrap();
+        assert_eq!((item0.id(), item1.id(), item2.id()), (0, 1, 2));
+
+        // An object keeps its id across checkouts
+        drop(item2);
+        assert_eq!(pool.take().unwrap().id(), 2);
+    }
+
+    #[test]
+    fn test_try_take_some() {
+        let pool = BundledPool::new(1, 2, move || TestObj { value: 42 });
+        let item = pool.try_take();
+        assert!(item.is_ok());
+        assert_eq!(item.as_ref().unwrap().value, 42);
+    }
+
+    #[test]
+    fn test_pool_return_and_reset() {
+        let pool = BundledPool::new(1, 2, move || make_test_obj(7));
+        {
+            let mut item = pool.take().unwrap();
+            item.value = 99;
+            // item dropped here, should be reset and returned to pool
+        }
+        assert_eq!(pool.available(), 1);
+        let item = pool.take().unwrap();
+        assert_eq!(item.value, 0); // Should be reset
+    }
+
+    #[test]
+    fn test_pool_detach() {
+        let pool = BundledPool::new(1, 2, move || make_test_obj(5));
+        let item = pool.take().unwrap();
+        let obj = item.detach();
+        assert_eq!(obj.value, 5);
+        assert_eq!(pool.available(), 0); // Not returned to pool
+    }
+
+    #[test]
+    fn test_try_take_none() {
+        let pool = BundledPool::new(0, 1, move || make_test_obj(1));
+        assert_eq!(pool.try_take().unwrap_err(), PoolError::Exhausted);
+    }
+
+    #[test]
+    fn test_pool_capacity() {
+        let pool = BundledPool::new(1, 3, move || make_test_obj(1));
+        assert_eq!(pool.capacity(), 3);
+    }
+
+    #[test]
+    fn test_arc_sharing_and_thread_safety() {
+        let pool = Arc::new(BundledPool::new(1, 2, move || make_test_obj(123)));
+
+        let mut handles = vec![];
+        for _ in 0..10 {
+            let pool = Arc::clone(&pool);
+            handles.push(thread::spawn(move || {
+                let mut item = pool.take().unwrap();
+                item.value += 1;
+            }));
+        }
+        for h in handles {
+            h.join().unwrap();
+        }
+        // used() == 0 proves every item was returned or discarded; available() guards capacity.
+        assert_eq!(pool.used(), 0);
+        assert!(pool.available() <= 2);
+    }
+
+    #[test]
+    fn test_pool_clone() {
+        let pool = BundledPool::new(1, 2, move || TestObj { value: 2 });
+        let pool_clone = pool.clone();
+        assert_eq!(pool.available(), pool_clone.available());
+
+        let _item = pool_clone.take().unwrap();
+        assert_eq!(pool.available(), 0);
+        assert_eq!(pool_clone.available(), 0);
+    }
+
+    #[test]
+    fn test_pool_data_debug_fmt() {
+        let pool = BundledPool::new(1, 2, move || TestObj { value: 3 });
+        let debug_str = format!("{:?}", pool);
+        assert!(debug_str.contains("PoolData"));
+        assert!(debug_str.contains("items"));
+        assert!(debug_str.contains("Box<dyn Fn() -> T>"));
+    }
+
+    #[test]
+    fn test_used_count() {
+        let pool = BundledPool::new(2, 4, move || TestObj { value: 1 });
+        assert_eq!(pool.used(), 0);
+
+        let item1 = pool.take().unwrap();
+        assert_eq!(pool.used(), 1);
+
+        let item2 = pool.take().unwrap();
+        assert_eq!(pool.used(), 2);
+
+        drop(item1);
+        assert_eq!(pool.used(), 1);
+
+        drop(item2);
+        assert_eq!(pool.used(), 0);
+    }
+
+    #[test]
+    fn test_bundled_pool_item_as_ref() {
+        let pool = BundledPool::new(1, 2, move || TestObj { value: 99 });
+        let item = pool.take().unwrap();
+        let inner_ref = item.as_ref();
+        assert_eq!(inner_ref.value, 99);
+    }
+
+    #[test]
+    fn test_bundled_pool_item_into_arc() {
+        let pool = BundledPool::new(1, 2, move || TestObj { value: 123 });
+        let item = pool.take().unwrap();
+        let arc_item = item.into_arc();
+        assert_eq!(arc_item.value, 123);
+    }
+
+    #[test]
+    fn test_bundled_pool_item_detach_comprehensive() {
+        let pool = BundledPool::new(2, 4, move || TestObj { value: 42 });
+
+        // Test detach removes item from pool tracking
+        let initial_available = pool.available();
+        let item = pool.take().unwrap();
+        assert_eq!(pool.available(), initial_available - 1);
+        assert_eq!(pool.used(), 1);
+
+        // Detach the item
+        let detached_obj = item.detach();
+        assert_eq!(detached_obj.value, 42);
+
+        // Pool should not track the detached item anymore
+        assert_eq!(pool.used(), 0);
+        assert_eq!(pool.available(), initial_available - 1); // Still one less available
+
+        // Detached object should not be returned to pool when dropped
+        #[allow(clippy::drop_non_drop)]
+        drop(detached_obj);
+        assert_eq!(pool.available(), initial_available - 1); // Still one less
+    }
+
+    #[test]
+    fn test_bundled_pool_item_detach_modified_object() {
+        let pool = BundledPool::new(1, 2, move || TestObj { value: 10 });
+
+        let mut item = pool.take().unwrap();
+        item.value = 999; // Modify the object
+
+        let detached_obj = item.detach();
+        assert_eq!(detached_obj.value, 999); // Should preserve modifications
+
+        // Take another item - should be newly created, not the modified one
+        let new_item = pool.take().unwrap();
+        assert_eq!(new_item.value, 10); // Fresh object from factory
+    }
+
+    #[test]
+    fn test_bundled_pool_item_deref() {
+        let pool = BundledPool::new(1, 2, move || TestObj { value: 777 });
+        let item = pool.take().unwrap();
+
+        // Test deref allows direct access to inner object
+        assert_eq!(item.value, 777);
+
+        // Test deref works with method calls
+        let debug_str = format!("{:?}", *item);
+        assert!(debug_str.contains("777"));
+
+        // Test deref works with pattern matching
+        match item.value {
+            777 => {}
+            _ => panic!("Deref not working correctly"),
+        }
+    }
+
+    #[test]
+    fn test_bundled_pool_item_deref_mut() {
+        let pool = BundledPool::new(1, 2, move || TestObj { value: 100 });
+        let mut item = pool.take().unwrap();
+
+        // Test deref_mut allows modification
+        item.value = 200;
+        assert_eq!(item.value, 200);
+
+        // Test deref_mut with compound assignment
+        item.value += 50;
+        assert_eq!(item.value, 250);
+
+        // Test deref_mut with method that takes &mut self
+        let old_value = item.value;
+        item.value *= 2;
+        assert_eq!(item.value, old_value * 2);
+
+        // When dropped, should be reset and returned to pool
+        drop(item);
+
+        let new_item = pool.take().unwrap();
+        assert_eq!(new_item.value, 0); // Should be reset
+    }
+
+    #[test]
+    fn test_bundled_pool_item_as_ref_comprehensive() {
+        let pool = BundledPool::new(1, 2, move || TestObj { value: 555 });
+        let item = pool.take().unwrap();
+
+        // Test as_ref returns correct reference
+        let obj_ref: &TestObj = item.as_ref();
+        assert_eq!(obj_ref.value, 555);
+
+        // Test as_ref can be used in generic contexts
+        fn check_value<T: AsRef<TestObj>>(item: &T) -> usize {
+            item.as_ref().value
+        }
+
+        assert_eq!(check_value(&item), 555);
+
+        // Test as_ref doesn't consume the item
+        let _ref1 = item.as_ref();
+        let _ref2 = item.as_ref(); // Should work multiple times
+        assert_eq!(item.value, 555); // Item still accessible
+    }
+
+    #[test]
+    fn test_bundled_pool_item_trait_interactions() {
+        let pool = BundledPool::new(1, 2, move || TestObj { value: 333 });
+        let mut item = pool.take().unwrap();
+
+        // Test that all traits work together
+        let as_ref_value = item.as_ref().value;
+        let deref_value = item.value;
+        assert_eq!(as_ref_value, deref_value);
+
+        // Modify through deref_mut
+        item.value = 444;
+
+        // Check through as_ref
+        assert_eq!(item.as_ref().value, 444);
+
+        // Check through deref
+        assert_eq!(item.value, 444);
+
+        // Detach and verify final state
+        let detached = item.detach();
+        assert_eq!(detached.value, 444);
+    }
+
+    #[test]
+    #[allow(clippy::explicit_auto_deref)]
+    fn test_bundled_pool_item_multiple_deref_patterns() {
+        let pool = BundledPool::new(1, 2, move || TestObj { value: 888 });
+        let mut item = pool.take().unwrap();
+
+        // Test various deref patterns
+        assert_eq!((*item).value, 888);
+        assert_eq!(item.value, 888);
+
+        // Test deref_mut patterns
+        (*item).value = 999;
+        assert_eq!(item.value, 999);
+
+        item.value = 111;
+        assert_eq!((*item).value, 111);
+    }
+
+    #[test]
+    fn test_bundled_pool_item_as_ref_with_generics() {
+        let pool = BundledPool::new(1, 2, move || TestObj { value: 666 });
+        let item = pool.take().unwrap();
+
+        // Test as_ref works in generic function
+        fn process_as_ref<T>(item: T) -> usize
+        where
+            T: AsRef<TestObj>,
+        {
+            item.as_ref().value
+        }
+
+        // Test as_ref works with borrowing
+        fn process_as_ref_borrowed<T>(item: &T) -> usize
+        where
+            T: AsRef<TestObj>,
+        {
+            item.as_ref().value
+        }
+
+        assert_eq!(process_as_ref_borrowed(&item), 666);
+        assert_eq!(process_as_ref(item), 666);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_take_async_reuses_idle_object() {
+        let pool = BundledPool::new(1, 2, move || TestObj { value: 1 });
+        let mut item = pool.take_async().await.unwrap();
+        item.value = 5;
+        drop(item);
+
+        let item = pool.take_async().await.unwrap();
+        assert_eq!(item.value, 0); // Reset, so not freshly created
+        assert_eq!(pool.available(), 0);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_take_async_creates_up_to_cap() {
+        let pool = BundledPool::new(0, 2, move || TestObj { value: 3 });
+        let item1 = pool.take_async().await.unwrap();
+        let item2 = pool.take_async().await.unwrap();
+        assert_eq!(item1.value, 3);
+        assert_eq!(item2.value, 3);
+        assert_eq!(pool.used(), 2);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_take_async_waits_for_release() {
+        let pool = BundledPool::new(0, 1, move || TestObj { value: 4 });
+        let mut item = pool.take_async().await.unwrap();
+        item.value = 9;
+
+        let waiter = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.take_async().await.unwrap().value }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        drop(item);
+        let value = tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("waiter should be woken by the drop")
+            .unwrap();
+        assert_eq!(value, 0); // Got the returned object, not a new one
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_take_async_wakes_on_detach() {
+        let pool = BundledPool::new(0, 1, move || TestObj { value: 8 });
+        let item = pool.take_async().await.unwrap();
+
+        let waiter = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.take_async().await.unwrap().value }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        let _detached = item.detach();
+        let value = tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("waiter should be woken by the detach")
+            .unwrap();
+        assert_eq!(value, 8); // Slot freed, so a fresh object was created
+    }
+
+    // on a paused clock, so that the sleeps end in order however loaded the machine is.
+    #[cfg(feature = "async")]
+    #[tokio::test(start_paused = true)]
+    async fn test_take_async_wakes_one_waiter_per_release() {
+        let pool = BundledPool::new(0, 1, move || TestObj { value: 1 });
+        let item = pool.take_async().await.unwrap();
+
+        let waiters: Vec<_> = (0..2)
+            .map(|_| {
+                let pool = pool.clone();
+                tokio::spawn(async move {
+                    let _item = pool.take_async().await.unwrap();
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                })
+            })
+            .collect();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        drop(item);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(waiters.iter().filter(|w| w.is_finished()).count(), 0);
+        assert_eq!(pool.used(), 1);
+
+        for waiter in waiters {
+            tokio::time::timeout(Duration::from_secs(1), waiter)
+                .await
+                .unwrap()
+                .unwrap();
+        }
+        assert_eq!(pool.used(), 0);
+        assert_eq!(pool.available(), 1);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_take_timeout_succeeds_when_available() {
+        let pool = BundledPool::new(1, 1, move || TestObj { value: 6 });
+        let item = pool.take_timeout(Duration::from_millis(10)).await.unwrap();
+        assert_eq!(item.value, 6);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_take_timeout_expires() {
+        let pool = BundledPool::new(0, 1, move || TestObj { value: 6 });
+        let _item = pool.take_async().await.unwrap();
+
+        let start = Instant::now();
+        let result = pool.take_timeout(Duration::from_millis(20)).await;
+        assert_eq!(result.unwrap_err(), PoolError::Timeout);
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_take_timeout_woken_by_release() {
+        let pool = BundledPool::new(0, 1, move || TestObj { value: 6 });
+        let item = pool.take_async().await.unwrap();
+
+        let waiter = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.take_timeout(Duration::from_secs(1)).await.is_ok() }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop(item);
+        assert!(waiter.await.unwrap());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_take_until_expired_deadline() {
+        let pool = BundledPool::new(0, 1, move || TestObj { value: 6 });
+        let _item = pool.take_async().await.unwrap();
+
+        let result = pool.take_until(Instant::now()).await;
+        assert_eq!(result.unwrap_err(), PoolError::Timeout);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_timed_out_waiter_does_not_swallow_wakeup() {
+        let pool = BundledPool::new(0, 1, move || TestObj { value: 6 });
+        let item = pool.take_async().await.unwrap();
+
+        assert!(pool.take_timeout(Duration::from_millis(10)).await.is_err());
+        let waiter = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.take_timeout(Duration::from_secs(1)).await.is_ok() }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop(item);
+        assert!(waiter.await.unwrap());
+    }
+
+    #[test]
+    fn test_try_new_initial_failure() {
+        let result = BundledPool::try_new(2, 4, || Err::<TestObj, _>("refused"));
+        assert_eq!(result.unwrap_err(), "refused");
+    }
+
+    #[test]
+    fn test_take_create_failed_releases_slot() {
+        let fail = Arc::new(AtomicBool::new(true));
+        let pool = BundledPool::try_new(0, 1, {
+            let fail = Arc::clone(&fail);
+            move || {
+                if fail.load(Ordering::Relaxed) {
+                    Err("refused")
+                } else {
+                    Ok(TestObj { value: 1 })
+                }
+            }
+        })
+        .unwrap();
+
+        assert_eq!(pool.take().unwrap_err(), PoolError::CreateFailed("refused"));
+        assert_eq!(pool.data.live.load(Ordering::Acquire), 0);
+
+        fail.store(false, Ordering::Relaxed);
+        assert_eq!(pool.take().unwrap().value, 1);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_take_async_create_failed_releases_slot() {
+        let fail = Arc::new(AtomicBool::new(true));
+        let pool = BundledPool::try_new(0, 1, {
+            let fail = Arc::clone(&fail);
+            move || {
+                if fail.load(Ordering::Relaxed) {
+                    Err("refused")
+                } else {
+                    Ok(TestObj { value: 1 })
+                }
+            }
+        })
+        .unwrap();
+
+        assert_eq!(
+            pool.take_async().await.unwrap_err(),
+            PoolError::CreateFailed("refused")
+        );
+
+        // The failed creation must not count against the cap of one.
+        fail.store(false, Ordering::Relaxed);
+        let item = pool.take_timeout(Duration::from_secs(1)).await.unwrap();
+        assert_eq!(item.value, 1);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_new_async_pre_allocates() {
+        let pool = BundledPool::new_async(2, 3, || async { Ok::<_, ()>(TestObj { value: 5 }) })
+            .await
+            .unwrap();
+        assert_eq!(pool.available(), 2);
+        assert_eq!(pool.take_async().await.unwrap().value, 5);
+    }
+
+    #[tokio::test]
+    async fn test_new_async_initial_failure() {
+        let result = BundledPool::new_async(1, 3, || async { Err::<TestObj, _>("refused") }).await;
+        assert_eq!(result.unwrap_err(), "refused");
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_async_factory_take() {
+        let pool = BundledPool::new_async(1, 2, || async { Ok::<_, ()>(TestObj { value: 5 }) })
+            .await
+            .unwrap();
+
+        // The idle object is handed out without awaiting the factory.
+        let item = pool.take().unwrap();
+        assert_eq!(pool.take().unwrap_err(), PoolError::Exhausted);
+        assert_eq!(pool.data.live.load(Ordering::Acquire), 1);
+
+        let created = pool.take_async().await.unwrap();
+        assert_eq!(created.value, 5);
+        drop(item);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_take_timeout_during_creation_releases_slot() {
+        let slow = Arc::new(AtomicBool::new(true));
+        let pool = BundledPool::new_async(0, 1, {
+            let slow = Arc::clone(&slow);
+            move || {
+                let slow = slow.load(Ordering::Relaxed);
+                async move {
+                    if slow {
+                        tokio::time::sleep(Duration::from_secs(60)).await;
+                    }
+                    Ok::<_, ()>(TestObj { value: 2 })
+                }
+            }
+        })
+        .await
+        .unwrap();
+
+        let result = pool.take_timeout(Duration::from_millis(10)).await;
+        assert_eq!(result.unwrap_err(), PoolError::Timeout);
+        assert_eq!(pool.data.live.load(Ordering::Acquire), 0);
+
+        slow.store(false, Ordering::Relaxed);
+        let item = pool.take_timeout(Duration::from_secs(1)).await.unwrap();
+        assert_eq!(item.value, 2);
+    }
+
+    #[test]
+    fn test_used_ignores_other_weak_references() {
+        let pool = BundledPool::new(1, 2, move || TestObj { value: 1 });
+        let _weak = Arc::downgrade(&pool.data);
+        assert_eq!(pool.used(), 0);
+
+        let _item = pool.take().unwrap();
+        assert_eq!(pool.used(), 1);
+    }
+
+    #[test]
+    fn test_stats_counters() {
+        let pool = BundledPool::new(1, 1, move || TestObj { value: 1 });
+        assert_eq!(
+            pool.stats(),
+            PoolStats {
+                idle: 1,
+                live: 1,
+                created: 1,
+                ..PoolStats::default()
+            }
+        );
+
+        let item1 = pool.take().unwrap();
+        let item2 = pool.take().unwrap();
+        let item3 = pool.take().unwrap();
+        let stats = pool.stats();
+        assert_eq!(stats.checked_out, 3);
+        assert_eq!(stats.live, 3);
+        assert_eq!(stats.created, 3);
+
+        drop(item1); // Goes back to the pool
+        drop(item2); // Pool is full, thrown away
+        let _obj = item3.detach();
+
+        let stats = pool.stats();
+        assert_eq!(stats.hold_time.count, 3);
+        assert_eq!(
+            stats,
+            PoolStats {
+                idle: 1,
+                live: 1,
+                checked_out: 0,
+                created: 3,
+                discarded_full: 1,
+                discarded_reset: 0,
+                discarded_broken: 0,
+                discarded_panic: 0,
+                discarded_invalid: 0,
+                discarded_expired: 0,
+                discarded_max_uses: 0,
+                discarded_closed: 0,
+                detached: 1,
+                resets: 2,
+                hits: 1,
+                misses: 2,
+                high_water: 3,
+                hold_time: stats.hold_time,
+                wait_time: stats.wait_time,
+            }
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_stats_hits_misses_and_high_water() {
+        let pool = BundledPool::new(1, 2, move || TestObj { value: 1 });
+
+        let item1 = pool.take_async().await.unwrap(); // hit
+        let item2 = pool.take_async().await.unwrap(); // miss
+        drop(item1);
+        let item3 = pool.try_take().unwrap(); // hit
+        assert!(pool.try_take().is_err()); // neither
+        drop((item2, item3));
+
+        let stats = pool.stats();
+        assert_eq!((stats.hits, stats.misses), (2, 1));
+        assert_eq!(stats.high_water, 2);
+    }
+
+    #[test]
+    fn test_reset_stats_keeps_gauges() {
+        let pool = BundledPool::new(0, 2, move || TestObj { value: 1 });
+        let item1 = pool.take().unwrap();
+        let item2 = pool.take().unwrap();
+        drop(item2);
+
+        pool.reset_stats();
+        let stats = pool.stats();
+        assert_eq!((stats.created, stats.misses, stats.resets), (0, 0, 0));
+        assert_eq!(stats.high_water, 1); // item1 is still checked out
+        assert_eq!((stats.idle, stats.live, stats.checked_out), (1, 2, 1));
+
+        let _item2 = pool.take().unwrap();
+        assert_eq!(pool.stats().high_water, 2);
+        drop(item1);
+    }
+
+    #[cfg(feature = "async")]
+    #[derive(Debug)]
+    struct Conn {
+        dirty: bool,
+    }
+
+    #[cfg(feature = "async")]
+    impl Resettable for Conn {
+        fn reset(&mut self) {}
+
+        fn try_reset(&mut self) -> ResetOutcome {
+            if self.dirty {
+                ResetOutcome::Discard
+            } else {
+                ResetOutcome::Keep
+            }
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_try_reset_discard_frees_slot() {
+        let pool = BundledPool::new(0, 1, || Conn { dirty: false });
+        let mut conn = pool.take_async().await.unwrap();
+        conn.dirty = true;
+
+        let waiter = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.take_async().await.map(|conn| conn.dirty) }
+        });
+        tokio::task::yield_now().await;
+        drop(conn);
+
+        // The waiter gets a fresh object rather than the discarded one
+        assert!(!waiter.await.unwrap().unwrap());
+        let stats = pool.stats();
+        assert_eq!(stats.discarded_reset, 1);
+        assert_eq!(stats.created, 2);
+        assert_eq!(stats.live, 1);
+        assert_eq!(pool.available(), 1);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_discard_frees_slot_and_runs_hook() {
+        let destroyed = Arc::new(AtomicUsize::new(0));
+        let pool = BundledPool::builder()
+            .max_live(1)
+            .factory(|| make_test_obj(1))
+            .on_destroy({
+                let destroyed = destroyed.clone();
+                move |obj: TestObj| {
+                    destroyed.fetch_add(obj.value, Ordering::SeqCst);
+                }
+            })
+            .build()
+            .unwrap();
+        let mut item = pool.take_async().await.unwrap();
+        item.value = 5;
+
+        let waiter = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.take_async().await.map(|item| item.value) }
+        });
+        tokio::task::yield_now().await;
+        item.discard();
+
+        assert_eq!(destroyed.load(Ordering::SeqCst), 5);
+        assert_eq!(waiter.await.unwrap().unwrap(), 1);
+        let stats = pool.stats();
+        assert_eq!(stats.discarded_broken, 1);
+        assert_eq!(stats.created, 2);
+        assert_eq!(stats.resets, 1);
+        assert_eq!(stats.live, 1);
+    }
+
+    #[test]
+    fn test_on_destroy_sees_every_discard() {
+        let destroyed = Arc::new(AtomicUsize::new(0));
+        let pool = BundledPool::builder()
+            .min_idle(1)
+            .max_idle(1)
+            .idle_timeout(Duration::from_millis(500))
+            // Objects fresh from the factory pass, reset ones fail
+            .validate(|obj: &TestObj| obj.value != 0)
+            .factory(|| make_test_obj(1))
+            .on_destroy({
+                let destroyed = destroyed.clone();
+                move |_| {
+                    destroyed.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+            .build()
+            .unwrap();
+        let destroyed = || destroyed.load(Ordering::SeqCst);
+
+        // Returned to a full pool
+        let item1 = pool.take().unwrap();
+        let item2 = pool.take().unwrap();
+        drop(item2);
+        drop(item1);
+        assert_eq!((destroyed(), pool.stats().discarded_full), (1, 1));
+
+        // Failing validation
+        drop(pool.take().unwrap());
+        assert_eq!((destroyed(), pool.stats().discarded_invalid), (2, 1));
+
+        // Evicted, once idle for at least the timeout
+        thread::sleep(Duration::from_millis(500));
+        assert_eq!(pool.evict_expired(), 1);
+        assert_eq!((destroyed(), pool.stats().discarded_expired), (3, 1));
+
+        // Idle when closed
+        drop(pool.take().unwrap());
+        pool.close();
+        assert_eq!((destroyed(), pool.stats().discarded_closed), (4, 1));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_close_with_panicking_destroy_hook() {
+        let destroyed = Arc::new(AtomicUsize::new(0));
+        let pool = BundledPool::builder()
+            .min_idle(3)
+            .max_idle(3)
+            .factory(|| make_test_obj(1))
+            .on_destroy({
+                let destroyed = destroyed.clone();
+                move |_| {
+                    destroyed.fetch_add(1, Ordering::SeqCst);
+                    panic!("failed to close");
+                }
+            })
+            .build()
+            .unwrap();
+        let item = pool.take().unwrap();
+
+        pool.close();
+        assert_eq!(destroyed.load(Ordering::SeqCst), 2);
+        drop(item);
+        assert_eq!(destroyed.load(Ordering::SeqCst), 3);
+        tokio::time::timeout(Duration::from_secs(1), pool.closed())
+            .await
+            .unwrap();
+        assert_eq!(pool.stats().discarded_closed, 3);
+    }
+
+    #[test]
+    fn test_on_destroy_when_pool_dropped() {
+        let destroyed = Arc::new(AtomicUsize::new(0));
+        let pool = BundledPool::builder()
+            .min_idle(2)
+            .max_idle(2)
+            .factory(|| make_test_obj(1))
+            .on_destroy({
+                let destroyed = destroyed.clone();
+                move |_| {
+                    destroyed.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+            .build()
+            .unwrap();
+        let item = pool.take().unwrap();
+
+        drop(pool);
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+        // Nowhere to go back to, so it is destroyed as well
+        drop(item);
+        assert_eq!(destroyed.load(Ordering::SeqCst), 2);
+    }
+
+    #[cfg(feature = "rt")]
+    #[tokio::test]
+    async fn test_on_destroy_async() {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let pool = BundledPool::builder()
+            .max_idle(1)
+            .factory(|| make_test_obj(1))
+            .on_destroy_async(move |obj: TestObj| {
+                let sender = sender.clone();
+                async move {
+                    tokio::task::yield_now().await;
+                    sender.send(obj).unwrap();
+                }
+            })
+            .build()
+            .unwrap();
+        let item1 = pool.take().unwrap();
+        let item2 = pool.take().unwrap();
+        drop(item1);
+        drop(item2);
+
+        // The slot is freed without waiting for the hook
+        assert_eq!(pool.stats().live, 1);
+        assert_eq!(receiver.recv().await, Some(make_test_obj(0)));
+    }
+
+    #[test]
+    fn test_mark_broken_destroys_on_drop() {
+        let pool = BundledPool::new(1, 2, || make_test_obj(7));
+        let mut item = pool.take().unwrap();
+        item.mark_broken();
+        item.value = 8;
+        assert_eq!(pool.stats().live, 1);
+        drop(item);
+
+        let stats = pool.stats();
+        assert_eq!(pool.available(), 0);
+        assert_eq!((stats.live, stats.checked_out), (0, 0));
+        assert_eq!((stats.discarded_broken, stats.resets), (1, 0));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_panicking_destroy_hook_frees_slot() {
+        let pool = BundledPool::builder()
+            .max_live(1)
+            .factory(|| make_test_obj(1))
+            .on_destroy(|_| panic!("failed to close"))
+            .build()
+            .unwrap();
+        pool.take_async().await.unwrap().discard();
+
+        let stats = pool.stats();
+        assert_eq!((stats.live, stats.checked_out), (0, 0));
+        assert_eq!(stats.discarded_broken, 1);
+        assert!(pool.take_timeout(Duration::from_secs(1)).await.is_ok());
+    }
+
+    #[test]
+    fn test_panicking_destroy_hook_during_unwind() {
+        let pool = BundledPool::builder()
+            .max_live(1)
+            .factory(|| make_test_obj(1))
+            .on_destroy(|_| panic!("failed to close"))
+            .build()
+            .unwrap();
+        let result = thread::spawn({
+            let pool = pool.clone();
+            move || {
+                let mut item = pool.take().unwrap();
+                item.mark_broken();
+                panic!("task failed while holding an item");
+            }
+        })
+        .join();
+
+        assert!(result.is_err());
+        let stats = pool.stats();
+        assert_eq!((stats.live, stats.discarded_broken), (0, 1));
+    }
+
+    // takes an item from `pool`, sets it to `value` and panics while holding it.
+    fn panic_holding(pool: &BundledPool<TestObj>, value: usize) {
+        let pool = pool.clone();
+        let result = thread::spawn(move || {
+            let mut item = pool.take().unwrap();
+            item.value = value;
+            panic!("task failed while holding an item");
+        })
+        .join();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_panicking_holder_returns_object_by_default() {
+        let pool = BundledPool::new(1, 1, || make_test_obj(1));
+        panic_holding(&pool, 9);
+        assert_eq!(pool.available(), 1);
+        assert_eq!(pool.take().unwrap().value, 0);
+    }
+
+    #[test]
+    fn test_discard_on_panic() {
+        let pool = BundledPool::builder()
+            .min_idle(1)
+            .max_idle(1)
+            .factory(|| make_test_obj(1))
+            .discard_on_panic()
+            .build()
+            .unwrap();
+        panic_holding(&pool, 9);
+
+        let stats = pool.stats();
+        assert_eq!(pool.available(), 0);
+        assert_eq!((stats.live, stats.checked_out), (0, 0));
+        assert_eq!(stats.discarded_panic, 1);
+        // Items dropped normally are still returned
+        drop(pool.take().unwrap());
+        assert_eq!(pool.available(), 1);
+    }
+
+    #[test]
+    fn test_on_panic_recovers_object() {
+        let pool = BundledPool::builder()
+            .min_idle(1)
+            .max_idle(1)
+            .factory(|| make_test_obj(1))
+            .on_panic(|obj: &mut TestObj| {
+                if obj.value < 10 {
+                    obj.value = 100;
+                    ResetOutcome::Keep
+                } else {
+                    ResetOutcome::Discard
+                }
+            })
+            .build()
+            .unwrap();
+
+        panic_holding(&pool, 9);
+        assert_eq!(pool.take().unwrap().value, 100);
+        panic_holding(&pool, 10);
+        assert_eq!(pool.available(), 0);
+        assert_eq!(pool.stats().discarded_panic, 1);
+    }
+
+    #[derive(Debug)]
+    struct Explosive;
+
+    impl Resettable for Explosive {
+        fn reset(&mut self) {
+            panic!("reset failed");
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_panicking_reset_frees_slot() {
+        let pool = BundledPool::new(0, 1, || Explosive);
+        let item = pool.take_async().await.unwrap();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| drop(item)));
+        assert!(result.is_err());
+
+        let stats = pool.stats();
+        assert_eq!((stats.live, stats.checked_out), (0, 0));
+        assert_eq!((stats.discarded_panic, stats.resets), (1, 0));
+        // The slot is free again, so the pool can create a replacement
+        let item = pool.take_timeout(Duration::from_secs(1)).await.unwrap();
+        item.detach();
+    }
+
+    #[test]
+    fn test_panicking_reset_during_unwind() {
+        let pool = BundledPool::new(0, 1, || Explosive);
+        let result = thread::spawn({
+            let pool = pool.clone();
+            move || {
+                let _item = pool.take().unwrap();
+                panic!("task failed while holding an item");
+            }
+        })
+        .join();
+
+        // The thread ends with its own panic rather than aborting the process
+        assert!(result.is_err());
+        let stats = pool.stats();
+        assert_eq!((stats.live, stats.discarded_panic), (0, 1));
+    }
+
+    #[test]
+    fn test_panicking_on_panic_hook_during_unwind() {
+        let destroyed = Arc::new(AtomicUsize::new(0));
+        let pool = BundledPool::builder()
+            .max_live(2)
+            .factory(|| make_test_obj(1))
+            .on_panic(|obj: &mut TestObj| match obj.value {
+                9 => panic!("cannot recover"),
+                _ => ResetOutcome::Discard,
+            })
+            .on_destroy({
+                let destroyed = destroyed.clone();
+                move |_| {
+                    destroyed.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+            .build()
+            .unwrap();
+
+        panic_holding(&pool, 9);
+        assert_eq!(destroyed.load(Ordering::SeqCst), 0);
+        // Discarded by the hook, so destroyed like any other
+        panic_holding(&pool, 2);
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+
+        let stats = pool.stats();
+        assert_eq!((stats.live, stats.discarded_panic), (0, 2));
+    }
+
+    fn panicking_on_return_pool() -> BundledPool<TestObj> {
+        BundledPool::builder()
+            .max_live(1)
+            .factory(|| make_test_obj(1))
+            .on_return(|_| panic!("hook failed"))
+            .build()
+            .unwrap()
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_panicking_on_return_frees_slot() {
+        let pool = panicking_on_return_pool();
+        let item = pool.take_async().await.unwrap();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| drop(item)));
+        assert!(result.is_err());
+
+        let stats = pool.stats();
+        assert_eq!((stats.idle, stats.live, stats.checked_out), (0, 0, 0));
+        assert_eq!(stats.discarded_panic, 1);
+        let item = pool.take_timeout(Duration::from_secs(1)).await.unwrap();
+        item.detach();
+    }
+
+    #[test]
+    fn test_panicking_on_return_during_unwind() {
+        let pool = panicking_on_return_pool();
+        panic_holding(&pool, 2);
+
+        let stats = pool.stats();
+        assert_eq!((stats.live, stats.discarded_panic), (0, 1));
+    }
+
+    #[cfg(feature = "rt")]
+    #[derive(Debug)]
+    struct Socket {
+        unflushed: usize,
+        healthy: bool,
     }
 
-    impl Resettable for TestObj {
+    #[cfg(feature = "rt")]
+    impl Resettable for Socket {
         fn reset(&mut self) {
-            self.value = 0;
+            self.unflushed = 0;
         }
     }
 
-    fn make_test_obj(val: usize) -> TestObj {
-        TestObj { value: val }
+    #[cfg(feature = "rt")]
+    impl AsyncResettable for Socket {
+        async fn reset_async(&mut self) -> ResetOutcome {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            assert!(self.healthy, "flush failed");
+            self.unflushed = 0;
+            ResetOutcome::Keep
+        }
     }
 
-    #[test]
-    fn test_pool_creation_and_take() {
-        let pool = BundledPool::new(2, 4, move || make_test_obj(42));
-        assert_eq!(pool.available(), 2);
+    #[cfg(feature = "rt")]
+    fn socket_pool(max_live: usize) -> BundledPool<Socket> {
+        BundledPool::builder()
+            .max_live(max_live)
+            .factory(|| Socket {
+                unflushed: 0,
+                healthy: true,
+            })
+            .async_reset()
+            .build()
+            .unwrap()
+    }
 
-        let item = pool.take();
-        assert_eq!(item.value, 42);
-        assert_eq!(pool.available(), 1);
+    #[cfg(feature = "rt")]
+    #[tokio::test(start_paused = true)]
+    async fn test_on_destroy_after_async_reset_outlives_pool() {
+        let destroyed = Arc::new(AtomicUsize::new(0));
+        let pool = BundledPool::builder()
+            .max_live(1)
+            .factory(|| Socket {
+                unflushed: 0,
+                healthy: true,
+            })
+            .async_reset()
+            .on_destroy({
+                let destroyed = destroyed.clone();
+                move |_| {
+                    destroyed.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+            .build()
+            .unwrap();
+        drop(pool.take().unwrap());
+        drop(pool);
+        assert_eq!(destroyed.load(Ordering::SeqCst), 0);
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
     }
 
-    #[test]
-    fn test_try_take_some() {
-        let pool = BundledPool::new(1, 2, move || TestObj { value: 42 });
-        let item = pool.try_take();
-        assert!(item.is_some());
-        assert_eq!(item.as_ref().unwrap().value, 42);
+    #[cfg(feature = "rt")]
+    #[tokio::test]
+    async fn test_async_reset_returns_object_when_done() {
+        let pool = socket_pool(1);
+        let mut socket = pool.take_async().await.unwrap();
+        socket.unflushed = 5;
+        drop(socket);
+
+        // The object keeps its slot while it is reset
+        let stats = pool.stats();
+        assert_eq!((stats.idle, stats.live, stats.checked_out), (0, 1, 0));
+        assert!(matches!(pool.try_take(), Err(PoolError::Exhausted)));
+
+        let socket = pool.take_timeout(Duration::from_secs(1)).await.unwrap();
+        assert_eq!(socket.unflushed, 0);
+        let stats = pool.stats();
+        assert_eq!((stats.created, stats.resets), (1, 1));
     }
 
-    #[test]
-    fn test_pool_return_and_reset() {
-        let pool = BundledPool::new(1, 2, move || make_test_obj(7));
-        {
-            let mut item = pool.take();
-            item.value = 99;
-            // item dropped here, should be reset and returned to pool
-        }
+    #[cfg(feature = "rt")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_wait_idle_waits_for_background_reset() {
+        let pool = socket_pool(1);
+        let mut socket = pool.take_async().await.unwrap();
+        socket.unflushed = 5;
+        drop(socket);
+
+        assert!(pool.wait_idle(Duration::from_secs(1)).await);
+        // Back in the pool, not just checked in
         assert_eq!(pool.available(), 1);
-        let item = pool.take();
-        assert_eq!(item.value, 0); // Should be reset
+        assert_eq!(pool.try_take().unwrap().unflushed, 0);
     }
 
+    #[cfg(feature = "rt")]
     #[test]
-    fn test_pool_detach() {
-        let pool = BundledPool::new(1, 2, move || make_test_obj(5));
-        let item = pool.take();
-        let obj = item.detach();
-        assert_eq!(obj.value, 5);
-        assert_eq!(pool.available(), 0); // Not returned to pool
-    }
+    fn test_async_reset_outside_runtime_resets_inline() {
+        let pool = socket_pool(1);
+        let mut socket = pool.take().unwrap();
+        socket.unflushed = 5;
+        drop(socket);
 
-    #[test]
-    fn test_try_take_none() {
-        let pool = BundledPool::new(0, 1, move || make_test_obj(1));
-        assert!(pool.try_take().is_none());
+        assert_eq!(pool.available(), 1);
+        assert_eq!(pool.take().unwrap().unflushed, 0);
     }
 
-    #[test]
-    fn test_pool_capacity() {
-        let pool = BundledPool::new(1, 3, move || make_test_obj(1));
-        assert_eq!(pool.capacity(), 3);
+    #[cfg(feature = "rt")]
+    #[tokio::test]
+    async fn test_async_reset_panic_frees_slot() {
+        let pool = socket_pool(1);
+        let mut socket = pool.take_async().await.unwrap();
+        socket.healthy = false;
+        drop(socket);
+
+        let socket = pool.take_timeout(Duration::from_secs(1)).await.unwrap();
+        assert!(socket.healthy);
+        let stats = pool.stats();
+        assert_eq!((stats.created, stats.discarded_panic), (2, 1));
     }
 
-    #[test]
-    fn test_arc_sharing_and_thread_safety() {
-        let pool = Arc::new(BundledPool::new(1, 2, move || make_test_obj(123)));
+    // counts its resets, which cannot be observed on the object once it is dropped.
+    #[derive(Debug)]
+    struct Tracked {
+        resets: Arc<AtomicUsize>,
+        value: usize,
+    }
 
-        let mut handles = vec![];
-        for _ in 0..10 {
-            let pool = Arc::clone(&pool);
-            handles.push(thread::spawn(move || {
-                let mut item = pool.take();
-                item.value += 1;
-            }));
-        }
-        for h in handles {
-            h.join().unwrap();
+    impl Resettable for Tracked {
+        fn reset(&mut self) {
+            self.resets.fetch_add(1, Ordering::SeqCst);
+            self.value = 0;
         }
-        // used() == 0 proves every item was returned or discarded; available() guards capacity.
-        assert_eq!(pool.used(), 0);
-        assert!(pool.available() <= 2);
+    }
+
+    fn tracked_pool(max_idle: usize, lazy: bool) -> (BundledPool<Tracked>, Arc<AtomicUsize>) {
+        let resets = Arc::new(AtomicUsize::new(0));
+        let builder = BundledPool::builder().max_idle(max_idle).factory({
+            let resets = resets.clone();
+            move || Tracked {
+                resets: resets.clone(),
+                value: 1,
+            }
+        });
+        let builder = if lazy { builder.lazy_reset() } else { builder };
+        (builder.build().unwrap(), resets)
     }
 
     #[test]
-    fn test_pool_clone() {
-        let pool = BundledPool::new(1, 2, move || TestObj { value: 2 });
-        let pool_clone = pool.clone();
-        assert_eq!(pool.available(), pool_clone.available());
+    fn test_lazy_reset_at_checkout() {
+        let (pool, resets) = tracked_pool(1, true);
+        let mut item = pool.take().unwrap();
+        item.value = 5;
+        drop(item);
+        assert_eq!(pool.available(), 1);
+        assert_eq!(resets.load(Ordering::SeqCst), 0);
 
-        let _item = pool_clone.take();
-        assert_eq!(pool.available(), 0);
-        assert_eq!(pool_clone.available(), 0);
+        assert_eq!(pool.try_take().unwrap().value, 0);
+        assert_eq!(resets.load(Ordering::SeqCst), 1);
+        assert_eq!(pool.stats().resets, 1);
     }
 
     #[test]
-    fn test_pool_data_debug_fmt() {
-        let pool = BundledPool::new(1, 2, move || TestObj { value: 3 });
-        let debug_str = format!("{:?}", pool);
-        assert!(debug_str.contains("PoolData"));
-        assert!(debug_str.contains("items"));
-        assert!(debug_str.contains("Box<dyn Fn() -> T>"));
+    fn test_lazy_reset_skips_overflow() {
+        let (pool, resets) = tracked_pool(1, true);
+        let item1 = pool.take().unwrap();
+        let item2 = pool.take().unwrap();
+        drop((item1, item2));
+
+        assert_eq!(pool.stats().discarded_full, 1);
+        assert_eq!(resets.load(Ordering::SeqCst), 0);
     }
 
     #[test]
-    fn test_used_count() {
-        let pool = BundledPool::new(2, 4, move || TestObj { value: 1 });
-        assert_eq!(pool.used(), 0);
-
-        let item1 = pool.take();
-        assert_eq!(pool.used(), 1);
+    fn test_no_reset_once_pool_dropped() {
+        for lazy in [false, true] {
+            let (pool, resets) = tracked_pool(1, lazy);
+            let item = pool.take().unwrap();
+            drop(pool);
+            drop(item);
+            assert_eq!(resets.load(Ordering::SeqCst), 0);
+        }
+    }
 
-        let item2 = pool.take();
-        assert_eq!(pool.used(), 2);
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_lazy_reset_discard_tries_next() {
+        let pool = BundledPool::builder()
+            .max_live(2)
+            .factory(|| Conn { dirty: false })
+            .lazy_reset()
+            .build()
+            .unwrap();
+        let mut conn1 = pool.take_async().await.unwrap();
+        let conn2 = pool.take_async().await.unwrap();
+        conn1.dirty = true;
+        drop(conn1);
+        drop(conn2);
+
+        // The dirty object is thrown away at checkout and the clean one behind it handed out
+        let conn1 = pool.take_async().await.unwrap();
+        let conn2 = pool.take_async().await.unwrap();
+        assert!(!conn1.dirty && !conn2.dirty);
+        let stats = pool.stats();
+        assert_eq!((stats.discarded_reset, stats.created), (1, 3));
+        assert_eq!((stats.hits, stats.misses), (1, 3));
+    }
 
-        drop(item1);
-        assert_eq!(pool.used(), 1);
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_validate_replaces_invalid_objects() {
+        let pool = BundledPool::builder()
+            .max_live(2)
+            .factory(|| make_test_obj(1))
+            .on_return(|obj: &mut TestObj| obj.value = 11)
+            .validate(|obj: &TestObj| obj.value != 11)
+            .build()
+            .unwrap();
+        let item1 = pool.take_async().await.unwrap();
+        let item2 = pool.take_async().await.unwrap();
+        drop((item1, item2));
+        assert_eq!(pool.available(), 2);
 
-        drop(item2);
-        assert_eq!(pool.used(), 0);
+        // Both idle objects fail validation, so a fresh one is created
+        let item = pool.take_async().await.unwrap();
+        assert_eq!(item.value, 1);
+        assert_eq!(pool.available(), 0);
+        let stats = pool.stats();
+        assert_eq!((stats.discarded_invalid, stats.live), (2, 1));
+        assert_eq!((stats.created, stats.hits), (3, 0));
     }
 
     #[test]
-    fn test_bundled_pool_item_as_ref() {
-        let pool = BundledPool::new(1, 2, move || TestObj { value: 99 });
-        let item = pool.take();
-        let inner_ref = item.as_ref();
-        assert_eq!(inner_ref.value, 99);
-    }
+    fn test_try_take_never_creates_after_invalid_object() {
+        let pool = BundledPool::builder()
+            .max_idle(1)
+            .factory(|| make_test_obj(0))
+            .validate(|_: &TestObj| false)
+            .build()
+            .unwrap();
+        drop(pool.take().unwrap());
+        assert_eq!(pool.available(), 1);
 
-    #[test]
-    fn test_bundled_pool_item_into_arc() {
-        let pool = BundledPool::new(1, 2, move || TestObj { value: 123 });
-        let item = pool.take();
-        let arc_item = item.into_arc();
-        assert_eq!(arc_item.value, 123);
+        assert!(matches!(pool.try_take(), Err(PoolError::Exhausted)));
+        let stats = pool.stats();
+        assert_eq!((stats.discarded_invalid, stats.created), (1, 1));
+        // `take` may create, and does not validate what the factory made
+        assert!(pool.take().is_ok());
     }
 
-    #[test]
-    fn test_bundled_pool_item_detach_comprehensive() {
-        let pool = BundledPool::new(2, 4, move || TestObj { value: 42 });
-
-        // Test detach removes item from pool tracking
-        let initial_available = pool.available();
-        let item = pool.take();
-        assert_eq!(pool.available(), initial_available - 1);
-        assert_eq!(pool.used(), 1);
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_panicking_validate_frees_slot() {
+        let pool = BundledPool::builder()
+            .max_live(1)
+            .factory(|| make_test_obj(1))
+            .validate(|_: &TestObj| panic!("cannot tell"))
+            .build()
+            .unwrap();
+        drop(pool.take_async().await.unwrap());
+        assert_eq!(pool.available(), 1);
 
-        // Detach the item
-        let detached_obj = item.detach();
-        assert_eq!(detached_obj.value, 42);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| pool.try_take().map(|_| ())));
+        assert!(result.is_err());
+        let stats = pool.stats();
+        assert_eq!((stats.idle, stats.live, stats.discarded_panic), (0, 0, 1));
+        // Objects fresh from the factory are not validated
+        assert!(pool.take_timeout(Duration::from_secs(1)).await.is_ok());
+    }
 
-        // Pool should not track the detached item anymore
-        assert_eq!(pool.used(), 0);
-        assert_eq!(pool.available(), initial_available - 1); // Still one less available
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_panicking_lazy_reset_frees_slot() {
+        let pool = BundledPool::builder()
+            .max_live(1)
+            .factory(|| Explosive)
+            .lazy_reset()
+            .build()
+            .unwrap();
+        drop(pool.take_async().await.unwrap());
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| pool.try_take().map(|_| ())));
+        assert!(result.is_err());
+        let stats = pool.stats();
+        assert_eq!((stats.idle, stats.live, stats.discarded_panic), (0, 0, 1));
+        let item = pool.take_timeout(Duration::from_secs(1)).await.unwrap();
+        item.detach();
+    }
 
-        // Detached object should not be returned to pool when dropped
-        drop(detached_obj);
-        assert_eq!(pool.available(), initial_available - 1); // Still one less
+    #[test]
+    fn test_idle_timeout_at_checkout() {
+        let pool = BundledPool::builder()
+            .min_idle(1)
+            .max_idle(1)
+            .idle_timeout(Duration::from_millis(20))
+            .factory(|| make_test_obj(1))
+            .build()
+            .unwrap();
+        // Sleeps for at least that long, so the object has expired however slow the machine is
+        thread::sleep(Duration::from_millis(20));
+
+        assert!(matches!(pool.try_take(), Err(PoolError::Exhausted)));
+        let stats = pool.stats();
+        assert_eq!((stats.discarded_expired, stats.live), (1, 0));
+        assert!(pool.take().is_ok());
     }
 
     #[test]
-    fn test_bundled_pool_item_detach_modified_object() {
-        let pool = BundledPool::new(1, 2, move || TestObj { value: 10 });
+    fn test_idle_timeout_restarts_on_return() {
+        let pool = BundledPool::builder()
+            .max_idle(1)
+            .idle_timeout(Duration::from_millis(250))
+            .factory(|| make_test_obj(1))
+            .build()
+            .unwrap();
+        let item = pool.take().unwrap();
+        // Held for as long as the idle timeout, which only counts time spent in the pool
+        thread::sleep(Duration::from_millis(250));
+        drop(item);
 
-        let mut item = pool.take();
-        item.value = 999; // Modify the object
+        assert!(pool.try_take().is_ok());
+        assert_eq!(pool.stats().discarded_expired, 0);
+    }
 
-        let detached_obj = item.detach();
-        assert_eq!(detached_obj.value, 999); // Should preserve modifications
+    #[test]
+    fn test_max_lifetime_on_return() {
+        let pool = BundledPool::builder()
+            .max_idle(2)
+            .max_lifetime(Duration::from_millis(20))
+            .factory(|| make_test_obj(1))
+            .build()
+            .unwrap();
+        let item = pool.take().unwrap();
+        thread::sleep(Duration::from_millis(20));
+        drop(item);
 
-        // Take another item - should be newly created, not the modified one
-        let new_item = pool.take();
-        assert_eq!(new_item.value, 10); // Fresh object from factory
+        let stats = pool.stats();
+        assert_eq!(pool.available(), 0);
+        assert_eq!(
+            (stats.discarded_expired, stats.live, stats.resets),
+            (1, 0, 0)
+        );
     }
 
     #[test]
-    fn test_bundled_pool_item_deref() {
-        let pool = BundledPool::new(1, 2, move || TestObj { value: 777 });
-        let item = pool.take();
-
-        // Test deref allows direct access to inner object
-        assert_eq!(item.value, 777);
+    fn test_evict_expired_keeps_fresh_objects() {
+        let pool = BundledPool::builder()
+            .max_idle(2)
+            .idle_timeout(Duration::from_millis(250))
+            .factory(|| make_test_obj(1))
+            .build()
+            .unwrap();
+        let item1 = pool.take().unwrap();
+        let item2 = pool.take().unwrap();
+        drop(item1);
+        // `item1` has expired by then; `item2` stays fresh for as long again
+        thread::sleep(Duration::from_millis(250));
+        drop(item2);
 
-        // Test deref works with method calls
-        let debug_str = format!("{:?}", *item);
-        assert!(debug_str.contains("777"));
+        assert_eq!(pool.evict_expired(), 1);
+        assert_eq!(pool.available(), 1);
+        assert_eq!(pool.evict_expired(), 0);
+        let stats = pool.stats();
+        assert_eq!((stats.discarded_expired, stats.live), (1, 1));
+    }
 
-        // Test deref works with pattern matching
-        match item.value {
-            777 => assert!(true),
-            _ => panic!("Deref not working correctly"),
+    #[test]
+    fn test_max_uses_recycles_object() {
+        let pool = BundledPool::builder()
+            .max_idle(1)
+            .max_uses(3)
+            .factory(|| make_test_obj(1))
+            .build()
+            .unwrap();
+        for uses in 1..=3 {
+            let item = pool.take().unwrap();
+            assert_eq!((item.id(), item.uses()), (0, uses));
         }
+        assert_eq!(pool.available(), 0);
+
+        let item = pool.take().unwrap();
+        assert_eq!((item.id(), item.uses()), (1, 1));
+        let stats = pool.stats();
+        assert_eq!(
+            (stats.discarded_max_uses, stats.created, stats.resets),
+            (1, 2, 2)
+        );
     }
 
-    #[test]
-    fn test_bundled_pool_item_deref_mut() {
-        let pool = BundledPool::new(1, 2, move || TestObj { value: 100 });
-        let mut item = pool.take();
+    #[cfg(feature = "rt")]
+    #[tokio::test]
+    async fn test_maintenance_tops_up_min_idle() {
+        let pool = BundledPool::builder()
+            .min_idle(2)
+            .max_idle(3)
+            .max_live(3)
+            .factory(|| make_test_obj(1))
+            .build()
+            .unwrap();
+        let items: Vec<_> = (0..3).map(|_| pool.take().unwrap()).collect();
+        pool.start_maintenance(Duration::from_millis(10));
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        // The live-object cap is reached, so nothing can be created
+        assert_eq!(pool.available(), 0);
 
-        // Test deref_mut allows modification
-        item.value = 200;
-        assert_eq!(item.value, 200);
+        for item in items {
+            item.detach();
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let stats = pool.stats();
+        assert_eq!((stats.idle, stats.live), (2, 2));
+        assert_eq!((stats.created, stats.misses), (5, 1));
+    }
 
-        // Test deref_mut with compound assignment
-        item.value += 50;
-        assert_eq!(item.value, 250);
+    #[cfg(feature = "rt")]
+    #[tokio::test]
+    #[should_panic(expected = "the maintenance interval must not be zero")]
+    async fn test_maintenance_rejects_zero_interval() {
+        let pool = BundledPool::new(0, 1, || make_test_obj(1));
+        pool.start_maintenance(Duration::ZERO);
+    }
 
-        // Test deref_mut with method that takes &mut self
-        let old_value = item.value;
-        item.value *= 2;
-        assert_eq!(item.value, old_value * 2);
+    #[cfg(feature = "rt")]
+    #[tokio::test]
+    async fn test_maintenance_does_not_keep_pool_alive_while_creating() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let pool = BundledPool::builder()
+            .min_idle(1)
+            .max_idle(1)
+            .async_factory({
+                let calls = calls.clone();
+                move || {
+                    let first = calls.fetch_add(1, Ordering::SeqCst) == 0;
+                    async move {
+                        // Only the pre-created object is ever finished
+                        if !first {
+                            std::future::pending::<()>().await;
+                        }
+                        Ok::<_, ()>(make_test_obj(1))
+                    }
+                }
+            })
+            .build_async()
+            .await
+            .unwrap();
+        pool.take_async().await.unwrap().detach();
+        pool.start_maintenance(Duration::from_millis(10));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let data = Arc::downgrade(&pool.data);
+        drop(pool);
+        assert!(data.upgrade().is_none());
+    }
 
-        // When dropped, should be reset and returned to pool
-        drop(item);
+    #[cfg(feature = "rt")]
+    #[tokio::test]
+    async fn test_maintenance_evicts_expired() {
+        let pool = BundledPool::builder()
+            .max_idle(2)
+            .idle_timeout(Duration::from_millis(20))
+            .factory(|| make_test_obj(1))
+            .build()
+            .unwrap();
+        let items = (pool.take().unwrap(), pool.take().unwrap());
+        drop(items);
+        pool.start_maintenance(Duration::from_millis(10));
+
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        assert_eq!(pool.available(), 0);
+        assert_eq!(pool.stats().discarded_expired, 2);
+    }
 
-        let new_item = pool.take();
-        assert_eq!(new_item.value, 0); // Should be reset
+    #[cfg(feature = "rt")]
+    #[tokio::test]
+    async fn test_maintenance_stops_with_pool() {
+        let factory_alive = Arc::new(());
+        let pool = BundledPool::new(1, 1, {
+            let factory_alive = factory_alive.clone();
+            move || {
+                let _ = &factory_alive;
+                make_test_obj(1)
+            }
+        });
+        pool.start_maintenance(Duration::from_millis(10));
+        pool.start_maintenance(Duration::from_millis(10));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(Arc::strong_count(&factory_alive), 2);
+
+        drop(pool);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        // The task lets go of the factory once it stopped
+        assert_eq!(Arc::strong_count(&factory_alive), 1);
     }
 
     #[test]
-    fn test_bundled_pool_item_as_ref_comprehensive() {
-        let pool = BundledPool::new(1, 2, move || TestObj { value: 555 });
-        let item = pool.take();
-
-        // Test as_ref returns correct reference
-        let obj_ref: &TestObj = item.as_ref();
-        assert_eq!(obj_ref.value, 555);
+    fn test_close_rejects_takes_and_drains() {
+        let pool = BundledPool::new(2, 4, move || TestObj { value: 1 });
+        let item = pool.take().unwrap();
+        let other = pool.clone();
 
-        // Test as_ref can be used in generic contexts
-        fn check_value<T: AsRef<TestObj>>(item: &T) -> usize {
-            item.as_ref().value
-        }
+        pool.close();
+        assert!(other.is_closed());
+        assert_eq!(pool.available(), 0);
+        assert!(matches!(other.take(), Err(PoolError::Closed)));
+        assert!(matches!(other.try_take(), Err(PoolError::Closed)));
 
-        assert_eq!(check_value(&item), 555);
+        // Returned objects are not kept either
+        drop(item);
+        let stats = pool.stats();
+        assert_eq!((stats.idle, stats.live), (0, 0));
+        assert_eq!(stats.discarded_closed, 2);
 
-        // Test as_ref doesn't consume the item
-        let _ref1 = item.as_ref();
-        let _ref2 = item.as_ref(); // Should work multiple times
-        assert_eq!(item.value, 555); // Item still accessible
+        pool.close();
+        assert_eq!(pool.stats().discarded_closed, 2);
     }
 
-    #[test]
-    fn test_bundled_pool_item_trait_interactions() {
-        let pool = BundledPool::new(1, 2, move || TestObj { value: 333 });
-        let mut item = pool.take();
-
-        // Test that all traits work together
-        let as_ref_value = item.as_ref().value;
-        let deref_value = item.value;
-        assert_eq!(as_ref_value, deref_value);
-
-        // Modify through deref_mut
-        item.value = 444;
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_close_while_creating() {
+        let gate = Arc::new(Notify::new());
+        let pool = BundledPool::builder()
+            .max_live(1)
+            .async_factory({
+                let gate = gate.clone();
+                move || {
+                    let gate = gate.clone();
+                    async move {
+                        gate.notified().await;
+                        Ok::<_, ()>(make_test_obj(1))
+                    }
+                }
+            })
+            .build()
+            .unwrap();
+
+        let taker = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.take_async().await.map(|_| ()) }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        pool.close();
+        gate.notify_one();
+
+        assert_eq!(taker.await.unwrap(), Err(PoolError::Closed));
+        let stats = pool.stats();
+        assert_eq!((stats.live, stats.discarded_closed), (0, 1));
+    }
 
-        // Check through as_ref
-        assert_eq!(item.as_ref().value, 444);
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_close_wakes_waiters() {
+        let pool = BundledPool::new(0, 1, move || TestObj { value: 1 });
+        let item = pool.take_async().await.unwrap();
+
+        let waiter = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.take_async().await.map(|_| ()) }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        pool.close();
+        assert_eq!(waiter.await.unwrap(), Err(PoolError::Closed));
+        drop(item);
+    }
 
-        // Check through deref
-        assert_eq!(item.value, 444);
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_closed_waits_for_outstanding_items() {
+        let pool = BundledPool::new(1, 4, move || TestObj { value: 1 });
+        let item1 = pool.take().unwrap();
+        let item2 = pool.take().unwrap();
+        pool.close();
+
+        let returner = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(item1);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            item2.detach();
+        });
+        pool.closed().await;
+        assert_eq!(pool.stats().live, 0);
+        returner.await.unwrap();
+        // Already settled
+        pool.closed().await;
+    }
 
-        // Detach and verify final state
-        let detached = item.detach();
-        assert_eq!(detached.value, 444);
+    #[cfg(feature = "async")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_wait_idle() {
+        let pool = BundledPool::new(1, 4, move || TestObj { value: 1 });
+        assert!(pool.wait_idle(Duration::from_millis(10)).await);
+
+        let item = pool.take().unwrap();
+        assert!(!pool.wait_idle(Duration::from_millis(10)).await);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(item);
+        });
+        assert!(pool.wait_idle(Duration::from_secs(5)).await);
+        // Not closed, so the object is kept
+        assert_eq!(pool.available(), 1);
     }
 
-    #[test]
-    fn test_bundled_pool_item_multiple_deref_patterns() {
-        let pool = BundledPool::new(1, 2, move || TestObj { value: 888 });
-        let mut item = pool.take();
+    #[cfg(feature = "rt")]
+    #[tokio::test]
+    async fn test_close_stops_maintenance() {
+        let pool = BundledPool::new(2, 4, move || TestObj { value: 1 });
+        pool.start_maintenance(Duration::from_millis(10));
+        pool.close();
 
-        // Test various deref patterns
-        assert_eq!((*item).value, 888);
-        assert_eq!(item.value, 888);
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert_eq!(pool.available(), 0);
+        assert_eq!(pool.stats().created, 2);
+    }
 
-        // Test deref_mut patterns
-        (*item).value = 999;
-        assert_eq!(item.value, 999);
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_stats_wait_time() {
+        let pool = BundledPool::new(0, 1, move || TestObj { value: 1 });
+        let item = pool.take_async().await.unwrap();
+
+        let waiter = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.take_async().await.map(|_| ()) }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop(item);
+        waiter.await.unwrap().unwrap();
 
-        item.value = 111;
-        assert_eq!((*item).value, 111);
+        // How long the waiter waited depends on when it was first polled, so only the samples are checked
+        assert_eq!(pool.stats().wait_time.count, 2);
     }
 
     #[test]
-    fn test_bundled_pool_item_as_ref_with_generics() {
-        let pool = BundledPool::new(1, 2, move || TestObj { value: 666 });
-        let item = pool.take();
-
-        // Test as_ref works in generic function
-        fn process_as_ref<T>(item: T) -> usize
-        where
-            T: AsRef<TestObj>,
-        {
-            item.as_ref().value
-        }
+    fn test_stats_hold_time() {
+        let pool = BundledPool::new(1, 2, move || TestObj { value: 1 });
+        let item = pool.take().unwrap();
+        thread::sleep(Duration::from_millis(20));
+        drop(item);
+        let _quick = pool.take().unwrap();
 
-        // Test as_ref works with borrowing
-        fn process_as_ref_borrowed<T>(item: &T) -> usize
-        where
-            T: AsRef<TestObj>,
-        {
-            item.as_ref().value
-        }
+        let hold_time = pool.stats().hold_time;
+        assert_eq!(hold_time.count, 1);
+        assert!(hold_time.max >= Duration::from_millis(20));
+        assert!(hold_time.p50 >= Duration::from_millis(20));
+        assert!(hold_time.p99 <= hold_time.max);
 
-        assert_eq!(process_as_ref_borrowed(&item), 666);
-        assert_eq!(process_as_ref(item), 666);
+        pool.reset_stats();
+        assert_eq!(pool.stats().hold_time.count, 0);
     }
 }
@@ -0,0 +1,363 @@
+use std::fmt::{self, Debug, Formatter, Write};
+use std::sync::Weak;
+use std::time::Duration;
+
+use crate::stats::{BUCKETS, DiscardReason};
+use crate::{BundledPool, PoolStats, Resettable};
+
+/// Renders the statistics of one or more pools in the Prometheus text exposition format.
+///
+/// Pools are registered once and sampled on every [`render`](Self::render), so the result can be served as is from an
+/// existing `/metrics` handler. The exporter only keeps weak references: a pool whose handles have all been dropped
+/// is left out of the output rather than kept alive.
+///
+/// Every sample is labelled with `pool="<name>"`, the name set with
+/// [`BundledPoolBuilder::name`](crate::BundledPoolBuilder::name). The families are:
+///
+/// * `object_pool_idle`, `object_pool_live` and `object_pool_capacity` gauges;
+/// * `object_pool_created_total` and `object_pool_discarded_total` counters, the latter with a `reason` label;
+/// * the `object_pool_wait_seconds` histogram of how long [`BundledPool::take_async`] took to hand out an object.
+///
+/// # Examples
+///
+/// ```
+/// use asyn_object_pool::{BundledPool, PrometheusExporter, Resettable};
+///
+/// #[derive(Debug)]
+/// struct Buffer(Vec<u8>);
+///
+/// impl Resettable for Buffer {
+///     fn reset(&mut self) { self.0.clear(); }
+/// }
+///
+/// let pool = BundledPool::builder()
+///     .name("buffers")
+///     .min_idle(2)
+///     .max_idle(4)
+///     .factory(|| Buffer(Vec::new()))
+///     .build()
+///     .unwrap();
+///
+/// let mut exporter = PrometheusExporter::new();
+/// exporter.register(&pool);
+///
+/// let text = exporter.render();
+/// assert!(text.contains("# TYPE object_pool_idle gauge\n"));
+/// assert!(text.contains("object_pool_idle{pool=\"buffers\"} 2\n"));
+/// assert!(text.contains("object_pool_capacity{pool=\"buffers\"} 4\n"));
+/// ```
+#[derive(Default)]
+pub struct PrometheusExporter {
+    pools: Vec<Weak<dyn Sampled>>,
+}
+
+// what the exporter reads from a pool, without knowing its object or error type.
+pub(crate) trait Sampled: Send + Sync {
+    fn sample(&self) -> Sample;
+}
+
+pub(crate) struct Sample {
+    pub(crate) name: String,
+    pub(crate) capacity: usize,
+    pub(crate) stats: PoolStats,
+    pub(crate) wait_counts: [u64; BUCKETS],
+    pub(crate) wait_sum: Duration,
+}
+
+impl Sample {
+    // discarded objects by reason.
+    fn discards(&self) -> impl Iterator<Item = (&'static str, u64)> + '_ {
+        DiscardReason::ALL
+            .into_iter()
+            .map(|reason| (reason.as_str(), self.stats.discarded(reason)))
+    }
+}
+
+// a metric's name, help text and value.
+type Family<V> = (&'static str, &'static str, fn(&Sample) -> V);
+
+impl PrometheusExporter {
+    /// Creates an exporter with no pools.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `pool` to the output of [`render`](Self::render).
+    ///
+    /// Registering several pools with the same name is allowed but yields duplicate series, which Prometheus rejects.
+    pub fn register<T, E>(&mut self, pool: &BundledPool<T, E>)
+    where
+        T: Resettable + Send + 'static,
+    {
+        self.pools.push(pool.sampler());
+    }
+
+    /// Renders the registered pools that are still alive.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        self.write_to(&mut out)
+            .expect("writing to a String never fails");
+        out
+    }
+
+    /// Like [`render`](Self::render), but writes into `out`.
+    pub fn write_to<W: Write>(&self, out: &mut W) -> fmt::Result {
+        let samples: Vec<(String, Sample)> = self
+            .pools
+            .iter()
+            .filter_map(Weak::upgrade)
+            .map(|pool| {
+                let sample = pool.sample();
+                (escape_label(&sample.name), sample)
+            })
+            .collect();
+
+        let gauges: [Family<usize>; 3] = [
+            ("object_pool_idle", "Objects waiting in the pool.", |s| {
+                s.stats.idle
+            }),
+            (
+                "object_pool_live",
+                "Objects owned by the pool, idle or checked out.",
+                |s| s.stats.live,
+            ),
+            (
+                "object_pool_capacity",
+                "Maximum number of idle objects kept by the pool.",
+                |s| s.capacity,
+            ),
+        ];
+        for (name, help, value) in gauges {
+            header(out, name, "gauge", help)?;
+            for (pool, sample) in &samples {
+                writeln!(out, "{}{{pool=\"{}\"}} {}", name, pool, value(sample))?;
+            }
+        }
+
+        let name = "object_pool_created_total";
+        header(out, name, "counter", "Objects built by the factory.")?;
+        for (pool, sample) in &samples {
+            writeln!(
+                out,
+                "{}{{pool=\"{}\"}} {}",
+                name, pool, sample.stats.created
+            )?;
+        }
+
+        let name = "object_pool_discarded_total";
+        header(out, name, "counter", "Objects thrown away by the pool.")?;
+        for (pool, sample) in &samples {
+            for (reason, count) in sample.discards() {
+                writeln!(
+                    out,
+                    "{}{{pool=\"{}\",reason=\"{}\"}} {}",
+                    name, pool, reason, count
+                )?;
+            }
+        }
+
+        let name = "object_pool_wait_seconds";
+        header(
+            out,
+            name,
+            "histogram",
+            "Time take_async took to hand out an object.",
+        )?;
+        for (pool, sample) in &samples {
+            write_histogram(out, name, pool, &sample.wait_counts, sample.wait_sum)?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for PrometheusExporter {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        f.debug_struct("PrometheusExporter")
+            .field("pools", &self.pools.len())
+            .finish()
+    }
+}
+
+fn header<W: Write>(out: &mut W, name: &str, kind: &str, help: &str) -> fmt::Result {
+    writeln!(out, "# HELP {} {}", name, help)?;
+    writeln!(out, "# TYPE {} {}", name, kind)
+}
+
+// bucket `i` of a `Histogram` holds the durations of at most 2^i µs, so its cumulative count is exactly the number
+// of samples `le = 2^i µs`. Every other bucket is exported, from 4 µs to 2^38 µs (about 76 hours); `+Inf` covers
+// the rest.
+fn write_histogram<W: Write>(
+    out: &mut W,
+    name: &str,
+    pool: &str,
+    counts: &[u64; BUCKETS],
+    sum: Duration,
+) -> fmt::Result {
+    let mut cumulative = 0;
+    for (i, count) in counts.iter().enumerate() {
+        cumulative += count;
+        if i >= 2 && i % 2 == 0 && i < BUCKETS - 1 {
+            let le = (1u64 << i) as f64 / 1e6;
+            writeln!(
+                out,
+                "{}_bucket{{pool=\"{}\",le=\"{}\"}} {}",
+                name, pool, le, cumulative
+            )?;
+        }
+    }
+    writeln!(
+        out,
+        "{}_bucket{{pool=\"{}\",le=\"+Inf\"}} {}",
+        name, pool, cumulative
+    )?;
+    writeln!(
+        out,
+        "{}_sum{{pool=\"{}\"}} {}",
+        name,
+        pool,
+        sum.as_secs_f64()
+    )?;
+    writeln!(out, "{}_count{{pool=\"{}\"}} {}", name, pool, cumulative)
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::Histogram;
+
+    #[derive(Debug)]
+    struct TestObj;
+
+    impl Resettable for TestObj {
+        fn reset(&mut self) {}
+    }
+
+    fn pool(name: &str, min_idle: usize, max_idle: usize) -> BundledPool<TestObj> {
+        BundledPool::builder()
+            .name(name)
+            .min_idle(min_idle)
+            .max_idle(max_idle)
+            .factory(|| TestObj)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_render_several_pools() {
+        let small = pool("small", 1, 1);
+        let large = pool("large", 2, 8);
+        let item1 = small.take().unwrap();
+        let item2 = small.take().unwrap();
+        drop((item1, item2)); // One of them is discarded
+
+        let mut exporter = PrometheusExporter::new();
+        exporter.register(&small);
+        exporter.register(&large);
+        let text = exporter.render();
+
+        for expected in [
+            "# HELP object_pool_live Objects owned by the pool, idle or checked out.\n\
+             # TYPE object_pool_live gauge\n\
+             object_pool_live{pool=\"small\"} 1\n\
+             object_pool_live{pool=\"large\"} 2\n",
+            "object_pool_idle{pool=\"large\"} 2\n",
+            "object_pool_capacity{pool=\"large\"} 8\n",
+            "# TYPE object_pool_created_total counter\n\
+             object_pool_created_total{pool=\"small\"} 2\n\
+             object_pool_created_total{pool=\"large\"} 2\n",
+            "object_pool_discarded_total{pool=\"small\",reason=\"full\"} 1\n\
+             object_pool_discarded_total{pool=\"small\",reason=\"reset\"} 0\n",
+        ] {
+            assert!(
+                text.contains(expected),
+                "missing {:?} in\n{}",
+                expected,
+                text
+            );
+        }
+        assert_eq!(text.matches("# TYPE object_pool_idle gauge").count(), 1);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_render_wait_histogram() {
+        let pool = pool("waits", 0, 1);
+        drop(pool.take_async().await.unwrap());
+        drop(pool.take_async().await.unwrap());
+
+        let mut exporter = PrometheusExporter::new();
+        exporter.register(&pool);
+        let text = exporter.render();
+
+        let buckets: Vec<&str> = text
+            .lines()
+            .filter(|line| line.starts_with("object_pool_wait_seconds_bucket"))
+            .collect();
+        assert_eq!(buckets.len(), 19 + 1);
+        assert!(
+            buckets[0]
+                .starts_with("object_pool_wait_seconds_bucket{pool=\"waits\",le=\"0.000004\"} ")
+        );
+        assert_eq!(
+            buckets[18],
+            "object_pool_wait_seconds_bucket{pool=\"waits\",le=\"274877.906944\"} 2"
+        );
+        assert_eq!(
+            buckets[19],
+            "object_pool_wait_seconds_bucket{pool=\"waits\",le=\"+Inf\"} 2"
+        );
+        assert!(text.contains("object_pool_wait_seconds_count{pool=\"waits\"} 2\n"));
+        assert!(text.contains("object_pool_wait_seconds_sum{pool=\"waits\"} "));
+    }
+
+    #[test]
+    fn test_histogram_bucket_bounds_are_inclusive() {
+        let histogram = Histogram::new();
+        histogram.record(Duration::from_micros(4));
+        histogram.record(Duration::from_nanos(4_001));
+        histogram.record(Duration::from_micros(16));
+
+        let mut text = String::new();
+        write_histogram(&mut text, "t", "p", &histogram.counts(), histogram.sum()).unwrap();
+        assert!(text.contains("t_bucket{pool=\"p\",le=\"0.000004\"} 1\n"));
+        assert!(text.contains("t_bucket{pool=\"p\",le=\"0.000016\"} 3\n"));
+    }
+
+    #[test]
+    fn test_render_skips_dropped_pools() {
+        let kept = pool("kept", 0, 1);
+        let dropped = pool("dropped", 0, 1);
+        let mut exporter = PrometheusExporter::new();
+        exporter.register(&kept);
+        exporter.register(&dropped);
+        drop(dropped);
+
+        let text = exporter.render();
+        assert!(text.contains("pool=\"kept\""));
+        assert!(!text.contains("pool=\"dropped\""));
+    }
+
+    #[test]
+    fn test_render_escapes_pool_name() {
+        let pool = pool("a \"quoted\" \\ name\n", 0, 1);
+        let mut exporter = PrometheusExporter::new();
+        exporter.register(&pool);
+
+        let text = exporter.render();
+        assert!(text.contains("object_pool_idle{pool=\"a \\\"quoted\\\" \\\\ name\\n\"} 0\n"));
+    }
+
+    #[test]
+    fn test_render_without_pools() {
+        let text = PrometheusExporter::new().render();
+        assert!(text.contains("# TYPE object_pool_wait_seconds histogram\n"));
+        assert!(!text.contains("pool="));
+    }
+}
@@ -1,5 +1,137 @@
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, VecDeque};
+
+/// An object that can be put back into its initial state before being handed out again.
+///
+/// Implemented for `Option`, `Box`, arrays and tuples of `Resettable` values, for the std collections (which are
+/// cleared but keep their allocated capacity) and for the numeric and `bool` primitives (which are set to zero and
+/// `false`).
 pub trait Resettable {
     fn reset(&mut self);
+
+    /// Resets the object, reporting whether it can be reused.
+    ///
+    /// This is what the pool calls when an item is dropped: on [`ResetOutcome::Discard`] the object is thrown away
+    /// instead of going back to the pool, freeing its slot. The default calls [`reset`](Self::reset) and keeps the
+    /// object; override it for objects that cannot always be cleaned, such as a connection left mid-transaction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use asyn_object_pool::{BundledPool, ResetOutcome, Resettable};
+    ///
+    /// #[derive(Debug)]
+    /// struct Connection { in_transaction: bool }
+    ///
+    /// impl Resettable for Connection {
+    ///     fn reset(&mut self) {}
+    ///
+    ///     fn try_reset(&mut self) -> ResetOutcome {
+    ///         if self.in_transaction {
+    ///             ResetOutcome::Discard
+    ///         } else {
+    ///             ResetOutcome::Keep
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let pool = BundledPool::new(1, 1, || Connection { in_transaction: false });
+    /// let mut conn = pool.take().unwrap();
+    /// conn.in_transaction = true;
+    /// drop(conn);
+    ///
+    /// assert_eq!(pool.available(), 0);
+    /// assert_eq!(pool.stats().discarded_reset, 1);
+    /// ```
+    fn try_reset(&mut self) -> ResetOutcome {
+        self.reset();
+        ResetOutcome::Keep
+    }
+}
+
+/// Whether an object can go back to the pool after [`Resettable::try_reset`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ResetOutcome {
+    /// The object is clean and can be reused.
+    #[default]
+    Keep,
+    /// The object must be thrown away.
+    Discard,
+}
+
+impl ResetOutcome {
+    /// Combines the outcomes of an object's parts: the object is kept only if all of them can be.
+    #[inline]
+    pub fn and(self, other: ResetOutcome) -> ResetOutcome {
+        if self == ResetOutcome::Discard {
+            self
+        } else {
+            other
+        }
+    }
+}
+
+/// An object whose reset has to `.await`, such as flushing a socket or rolling back a transaction.
+///
+/// Pools built with [`BundledPoolBuilder::async_reset`](crate::BundledPoolBuilder::async_reset) call
+/// [`reset_async`](Self::reset_async) instead of [`Resettable::try_reset`] on returned objects. As
+/// [`BundledPoolItem`](crate::BundledPoolItem) is dropped synchronously, the reset runs in a task spawned on the
+/// current tokio runtime, and the object only goes back to the pool once it completes. That builder method needs the
+/// `rt` feature, enabled by default.
+///
+/// # Examples
+///
+/// ```
+/// use asyn_object_pool::{AsyncResettable, BundledPool, ResetOutcome, Resettable};
+/// use std::time::Duration;
+///
+/// #[derive(Debug)]
+/// struct Socket { unflushed: Vec<u8> }
+///
+/// impl Socket {
+///     async fn flush(&mut self) -> Result<(), std::io::Error> {
+///         tokio::time::sleep(Duration::from_millis(1)).await;
+///         self.unflushed.clear();
+///         Ok(())
+///     }
+/// }
+///
+/// impl Resettable for Socket {
+///     fn reset(&mut self) { self.unflushed.clear(); }
+/// }
+///
+/// impl AsyncResettable for Socket {
+///     async fn reset_async(&mut self) -> ResetOutcome {
+///         match self.flush().await {
+///             Ok(()) => ResetOutcome::Keep,
+///             Err(_) => ResetOutcome::Discard,
+///         }
+///     }
+/// }
+///
+/// # #[cfg(not(feature = "rt"))]
+/// # fn main() {}
+/// # #[cfg(feature = "rt")]
+/// # #[tokio::main]
+/// # async fn main() {
+/// let pool = BundledPool::builder()
+///     .max_idle(4)
+///     .factory(|| Socket { unflushed: Vec::new() })
+///     .async_reset()
+///     .build()
+///     .unwrap();
+///
+/// let mut socket = pool.take_async().await.unwrap();
+/// socket.unflushed.extend_from_slice(b"hello");
+/// drop(socket);
+///
+/// // The socket is back once its flush completed
+/// let socket = pool.take_timeout(Duration::from_secs(1)).await.unwrap();
+/// assert!(socket.unflushed.is_empty());
+/// # }
+/// ```
+pub trait AsyncResettable {
+    /// Resets the object, reporting whether it can be reused; see [`Resettable::try_reset`].
+    fn reset_async(&mut self) -> impl Future<Output = ResetOutcome> + Send;
 }
 
 impl<T> Resettable for Option<T>
@@ -11,19 +143,107 @@ where
             x.reset();
         }
     }
+
+    fn try_reset(&mut self) -> ResetOutcome {
+        match self {
+            Some(x) => x.try_reset(),
+            None => ResetOutcome::Keep,
+        }
+    }
 }
 
-impl<T1, T2> Resettable for (T1, T2)
-where
-    T1: Resettable,
-    T2: Resettable,
-{
+impl<T: Resettable + ?Sized> Resettable for Box<T> {
     fn reset(&mut self) {
-        self.0.reset();
-        self.1.reset();
+        (**self).reset();
     }
+
+    fn try_reset(&mut self) -> ResetOutcome {
+        (**self).try_reset()
+    }
+}
+
+impl<T: Resettable, const N: usize> Resettable for [T; N] {
+    fn reset(&mut self) {
+        for x in self {
+            x.reset();
+        }
+    }
+
+    fn try_reset(&mut self) -> ResetOutcome {
+        self.iter_mut()
+            .fold(ResetOutcome::Keep, |outcome, x| outcome.and(x.try_reset()))
+    }
+}
+
+macro_rules! impl_resettable_tuple {
+    ($($name:ident $index:tt),+) => {
+        impl<$($name: Resettable),+> Resettable for ($($name,)+) {
+            fn reset(&mut self) {
+                $(self.$index.reset();)+
+            }
+
+            fn try_reset(&mut self) -> ResetOutcome {
+                ResetOutcome::Keep$(.and(self.$index.try_reset()))+
+            }
+        }
+    };
 }
 
+impl_resettable_tuple!(T0 0);
+impl_resettable_tuple!(T0 0, T1 1);
+impl_resettable_tuple!(T0 0, T1 1, T2 2);
+impl_resettable_tuple!(T0 0, T1 1, T2 2, T3 3);
+impl_resettable_tuple!(T0 0, T1 1, T2 2, T3 3, T4 4);
+impl_resettable_tuple!(T0 0, T1 1, T2 2, T3 3, T4 4, T5 5);
+impl_resettable_tuple!(T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6);
+impl_resettable_tuple!(T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7);
+impl_resettable_tuple!(T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8);
+impl_resettable_tuple!(T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9);
+impl_resettable_tuple!(T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, T10 10);
+impl_resettable_tuple!(T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, T10 10, T11 11);
+
+// `clear` drops the contents but keeps the allocation, which is the point of pooling them.
+macro_rules! impl_resettable_clear {
+    ($($ty:ty => [$($generics:tt)*]),+ $(,)?) => {
+        $(
+            impl<$($generics)*> Resettable for $ty {
+                #[inline]
+                fn reset(&mut self) {
+                    self.clear();
+                }
+            }
+        )+
+    };
+}
+
+impl_resettable_clear!(
+    String => [],
+    Vec<T> => [T],
+    VecDeque<T> => [T],
+    HashMap<K, V, S> => [K, V, S],
+    HashSet<T, S> => [T, S],
+    BTreeMap<K, V> => [K, V],
+    BTreeSet<T> => [T],
+    BinaryHeap<T> => [T],
+);
+
+macro_rules! impl_resettable_primitive {
+    ($value:expr => $($ty:ty),+) => {
+        $(
+            impl Resettable for $ty {
+                #[inline]
+                fn reset(&mut self) {
+                    *self = $value;
+                }
+            }
+        )+
+    };
+}
+
+impl_resettable_primitive!(0 => u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+impl_resettable_primitive!(0.0 => f32, f64);
+impl_resettable_primitive!(false => bool);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -63,4 +283,331 @@ mod tests {
         t.reset();
         assert_eq!(t, (Dummy { value: 0 }, Dummy { value: 0 }));
     }
+
+    #[derive(Debug, PartialEq)]
+    struct Fragile {
+        broken: bool,
+    }
+
+    impl Resettable for Fragile {
+        fn reset(&mut self) {}
+
+        fn try_reset(&mut self) -> ResetOutcome {
+            if self.broken {
+                ResetOutcome::Discard
+            } else {
+                ResetOutcome::Keep
+            }
+        }
+    }
+
+    #[test]
+    fn test_try_reset_defaults_to_reset() {
+        let mut d = Dummy { value: 42 };
+        assert_eq!(d.try_reset(), ResetOutcome::Keep);
+        assert_eq!(d.value, 0);
+    }
+
+    #[test]
+    fn test_try_reset_forwarded_by_wrappers() {
+        let broken = || Fragile { broken: true };
+        let fine = || Fragile { broken: false };
+
+        assert_eq!(Some(broken()).try_reset(), ResetOutcome::Discard);
+        assert_eq!(None::<Fragile>.try_reset(), ResetOutcome::Keep);
+        assert_eq!(Box::new(broken()).try_reset(), ResetOutcome::Discard);
+        assert_eq!([fine(), broken()].try_reset(), ResetOutcome::Discard);
+        assert_eq!([fine(), fine()].try_reset(), ResetOutcome::Keep);
+
+        // Every element is still reset when one asks to be discarded
+        let mut tuple = (broken(), Dummy { value: 3 });
+        assert_eq!(tuple.try_reset(), ResetOutcome::Discard);
+        assert_eq!(tuple.1.value, 0);
+    }
+
+    #[test]
+    fn test_resettable_for_long_tuple() {
+        let mut t = (
+            1u8,
+            2u16,
+            3u32,
+            4u64,
+            5u128,
+            6usize,
+            -7i8,
+            true,
+            9.5f32,
+            -10.5f64,
+            'x'.to_string(),
+            vec![12],
+        );
+        t.reset();
+        assert_eq!(
+            t,
+            (0, 0, 0, 0, 0, 0, 0, false, 0.0, 0.0, String::new(), vec![])
+        );
+    }
+
+    #[test]
+    fn test_resettable_for_box_and_array() {
+        let mut boxed = Box::new(Dummy { value: 3 });
+        boxed.reset();
+        assert_eq!(boxed.value, 0);
+
+        let mut unsized_box: Box<dyn Resettable> = Box::new(Dummy { value: 3 });
+        unsized_box.reset();
+
+        let mut array = [Dummy { value: 1 }, Dummy { value: 2 }];
+        array.reset();
+        assert_eq!(array, [Dummy { value: 0 }, Dummy { value: 0 }]);
+    }
+
+    #[test]
+    fn test_resettable_for_collections_keeps_capacity() {
+        let mut vec: Vec<u8> = Vec::with_capacity(100);
+        vec.extend_from_slice(b"data");
+        vec.reset();
+        assert!(vec.is_empty());
+        assert!(vec.capacity() >= 100);
+
+        let mut string = String::with_capacity(100);
+        string.push_str("data");
+        string.reset();
+        assert!(string.is_empty());
+        assert!(string.capacity() >= 100);
+
+        let mut deque: VecDeque<u8> = VecDeque::with_capacity(100);
+        deque.push_back(1);
+        deque.reset();
+        assert!(deque.is_empty());
+        assert!(deque.capacity() >= 100);
+
+        let mut map: HashMap<&str, u8> = HashMap::with_capacity(100);
+        map.insert("a", 1);
+        map.reset();
+        assert!(map.is_empty());
+        assert!(map.capacity() >= 100);
+
+        let mut set: HashSet<u8> = HashSet::with_capacity(100);
+        set.insert(1);
+        set.reset();
+        assert!(set.is_empty());
+        assert!(set.capacity() >= 100);
+
+        let mut heap: BinaryHeap<u8> = BinaryHeap::with_capacity(100);
+        heap.push(1);
+        heap.reset();
+        assert!(heap.is_empty());
+        assert!(heap.capacity() >= 100);
+
+        let mut btree_map = BTreeMap::from([(1, 2)]);
+        btree_map.reset();
+        assert!(btree_map.is_empty());
+
+        let mut btree_set = BTreeSet::from([1]);
+        btree_set.reset();
+        assert!(btree_set.is_empty());
+    }
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod derive_tests {
+    use crate::{ResetOutcome, Resettable};
+    use std::marker::PhantomData;
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Counter {
+        count: u32,
+    }
+
+    impl Resettable for Counter {
+        fn reset(&mut self) {
+            self.count = 0;
+        }
+    }
+
+    fn clear_and_mark(names: &mut Vec<String>) {
+        names.clear();
+        names.push("reset".to_string());
+    }
+
+    #[derive(Debug, PartialEq, Resettable)]
+    struct Session {
+        #[reset(skip)]
+        id: u32,
+        counter: Counter,
+        maybe: Option<Counter>,
+        #[reset(default)]
+        label: String,
+        #[reset(value = 30 * 2)]
+        timeout: u64,
+        #[reset(with = clear_and_mark)]
+        names: Vec<String>,
+    }
+
+    #[test]
+    fn test_derive_struct_attributes() {
+        let mut session = Session {
+            id: 9,
+            counter: Counter { count: 3 },
+            maybe: Some(Counter { count: 4 }),
+            label: "busy".to_string(),
+            timeout: 1,
+            names: vec!["a".to_string(), "b".to_string()],
+        };
+        session.reset();
+        assert_eq!(
+            session,
+            Session {
+                id: 9,
+                counter: Counter { count: 0 },
+                maybe: Some(Counter { count: 0 }),
+                label: String::new(),
+                timeout: 60,
+                names: vec!["reset".to_string()],
+            }
+        );
+    }
+
+    struct Link {
+        open: bool,
+    }
+
+    impl Resettable for Link {
+        fn reset(&mut self) {}
+
+        fn try_reset(&mut self) -> ResetOutcome {
+            if self.open {
+                ResetOutcome::Keep
+            } else {
+                ResetOutcome::Discard
+            }
+        }
+    }
+
+    #[derive(Resettable)]
+    struct Client {
+        link: Link,
+        counter: Counter,
+        #[reset(default)]
+        retries: u32,
+    }
+
+    #[test]
+    fn test_derive_try_reset_combines_fields() {
+        let mut client = Client {
+            link: Link { open: true },
+            counter: Counter { count: 1 },
+            retries: 3,
+        };
+        assert_eq!(client.try_reset(), ResetOutcome::Keep);
+
+        client.link.open = false;
+        client.counter.count = 2;
+        client.retries = 4;
+        assert_eq!(client.try_reset(), ResetOutcome::Discard);
+        // The other fields are reset all the same
+        assert_eq!((client.counter.count, client.retries), (0, 0));
+    }
+
+    #[derive(Debug, PartialEq, Resettable)]
+    struct Pair(Counter, #[reset(skip)] u8);
+
+    #[derive(Debug, PartialEq, Resettable)]
+    struct Unit;
+
+    #[test]
+    fn test_derive_tuple_and_unit_structs() {
+        let mut pair = Pair(Counter { count: 1 }, 2);
+        pair.reset();
+        assert_eq!(pair, Pair(Counter { count: 0 }, 2));
+
+        let mut unit = Unit;
+        unit.reset();
+        assert_eq!(unit, Unit);
+    }
+
+    // `T` is only bounded where a field needs it, so `PhantomData<U>` does not require `U: Resettable`.
+    #[derive(Resettable)]
+    struct Wrapper<T, U> {
+        inner: T,
+        #[reset(skip)]
+        marker: PhantomData<U>,
+    }
+
+    #[test]
+    fn test_derive_generic_struct() {
+        struct NotResettable;
+        let mut wrapper = Wrapper::<Counter, NotResettable> {
+            inner: Counter { count: 5 },
+            marker: PhantomData,
+        };
+        wrapper.reset();
+        assert_eq!(wrapper.inner.count, 0);
+    }
+
+    #[derive(Debug, PartialEq, Resettable)]
+    enum State {
+        Closed,
+        Failed(String),
+        #[reset(default)]
+        Open {
+            #[reset(value = 8)]
+            window: u32,
+            counter: Counter,
+            #[reset(with = Vec::clear)]
+            pending: Vec<u8>,
+        },
+    }
+
+    #[test]
+    fn test_derive_enum_resets_to_marked_variant() {
+        for mut state in [State::Closed, State::Failed("timeout".to_string())] {
+            state.reset();
+            assert_eq!(
+                state,
+                State::Open {
+                    window: 8,
+                    counter: Counter::default(),
+                    pending: Vec::new(),
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_derive_enum_resets_marked_variant_in_place() {
+        let mut state = State::Open {
+            window: 1,
+            counter: Counter { count: 2 },
+            pending: Vec::with_capacity(64),
+        };
+        state.reset();
+        match state {
+            State::Open {
+                window,
+                counter,
+                pending,
+            } => {
+                assert_eq!(window, 8);
+                assert_eq!(counter.count, 0);
+                assert!(pending.is_empty());
+                assert!(pending.capacity() >= 64); // Kept its allocation
+            }
+            _ => panic!("expected State::Open"),
+        }
+    }
+
+    #[derive(Debug, PartialEq, Resettable)]
+    enum Single {
+        #[reset(default)]
+        Only(Counter),
+    }
+
+    #[test]
+    fn test_derive_single_variant_enum() {
+        let mut single = Single::Only(Counter { count: 1 });
+        single.reset();
+        assert_eq!(single, Single::Only(Counter { count: 0 }));
+    }
 }
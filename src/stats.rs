@@ -0,0 +1,421 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+
+// power-of-two microsecond buckets; the last one also takes everything longer (2^38 µs is over three days).
+pub(crate) const BUCKETS: usize = 40;
+
+/// A point-in-time snapshot of a pool's counters, returned by `BundledPool::stats`.
+///
+/// `idle`, `live` and `checked_out` are gauges; the other fields count events since the pool was created or since
+/// `BundledPool::reset_stats` was last called. Each field is read independently, so a snapshot taken while other
+/// threads use the pool is not atomic as a whole.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Objects waiting in the pool.
+    pub idle: usize,
+    /// Objects owned by the pool, idle or checked out.
+    pub live: usize,
+    /// Objects currently held by a `BundledPoolItem`.
+    pub checked_out: usize,
+    /// Objects built by the factory, including pre-created ones.
+    pub created: u64,
+    /// Returned objects thrown away because the pool already held its maximum of idle objects.
+    pub discarded_full: u64,
+    /// Returned objects thrown away because [`Resettable::try_reset`](crate::Resettable::try_reset) asked for it.
+    pub discarded_reset: u64,
+    /// Objects thrown away with `BundledPoolItem::discard` or `BundledPoolItem::mark_broken`.
+    pub discarded_broken: u64,
+    /// Objects thrown away because their reset panicked, or because they were dropped during a panic and the pool was
+    /// told to discard those.
+    pub discarded_panic: u64,
+    /// Idle objects thrown away at checkout because they failed the pool's `validate` hook.
+    pub discarded_invalid: u64,
+    /// Objects thrown away because they outlived the pool's `idle_timeout` or `max_lifetime`.
+    pub discarded_expired: u64,
+    /// Objects thrown away on return because they had been checked out `max_uses` times.
+    pub discarded_max_uses: u64,
+    /// Objects thrown away because the pool was closed: the idle ones at the time and those returned afterwards.
+    pub discarded_closed: u64,
+    /// Objects taken out of the pool with `BundledPoolItem::detach`.
+    pub detached: u64,
+    /// Objects reset on their way back to the pool.
+    pub resets: u64,
+    /// Checkouts served by an idle object.
+    pub hits: u64,
+    /// Checkouts served by creating a new object.
+    pub misses: u64,
+    /// The largest number of objects checked out at the same time.
+    pub high_water: usize,
+    /// How long objects were held between checkout and drop or detach.
+    pub hold_time: DurationStats,
+    /// How long `take_async` (and the methods built on it) took to hand out an object, including creating it.
+    pub wait_time: DurationStats,
+}
+
+/// Percentiles of a recorded duration, part of [`PoolStats`].
+///
+/// Durations are bucketed by powers of two microseconds, so percentiles are rounded up to the end of their bucket
+/// (but never past `max`): a percentile may overstate the durations it stands for by up to 2x, e.g. a p99 of 65 µs
+/// may be reported as 128 µs. They show orders of magnitude rather than exact latencies; `count` and `max` are exact.
+/// All fields are zero when nothing was recorded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DurationStats {
+    /// Number of recorded durations.
+    pub count: u64,
+    /// 50th percentile (median).
+    pub p50: Duration,
+    /// 90th percentile.
+    pub p90: Duration,
+    /// 99th percentile.
+    pub p99: Duration,
+    /// The longest recorded duration.
+    pub max: Duration,
+}
+
+impl PoolStats {
+    pub(crate) fn discarded(&self, reason: DiscardReason) -> u64 {
+        match reason {
+            DiscardReason::Full => self.discarded_full,
+            DiscardReason::Reset => self.discarded_reset,
+            DiscardReason::Broken => self.discarded_broken,
+            DiscardReason::Panic => self.discarded_panic,
+            DiscardReason::Invalid => self.discarded_invalid,
+            DiscardReason::Expired => self.discarded_expired,
+            DiscardReason::MaxUses => self.discarded_max_uses,
+            DiscardReason::Closed => self.discarded_closed,
+        }
+    }
+}
+
+// why the pool threw an object away; each reason has its own counter in `PoolStats`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DiscardReason {
+    Full,
+    Reset,
+    Broken,
+    Panic,
+    Invalid,
+    Expired,
+    MaxUses,
+    Closed,
+}
+
+impl DiscardReason {
+    pub(crate) const ALL: [DiscardReason; 8] = [
+        DiscardReason::Full,
+        DiscardReason::Reset,
+        DiscardReason::Broken,
+        DiscardReason::Panic,
+        DiscardReason::Invalid,
+        DiscardReason::Expired,
+        DiscardReason::MaxUses,
+        DiscardReason::Closed,
+    ];
+
+    // the `reason` label in metrics and traces.
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            DiscardReason::Full => "full",
+            DiscardReason::Reset => "reset",
+            DiscardReason::Broken => "broken",
+            DiscardReason::Panic => "panic",
+            DiscardReason::Invalid => "invalid",
+            DiscardReason::Expired => "expired",
+            DiscardReason::MaxUses => "max_uses",
+            DiscardReason::Closed => "closed",
+        }
+    }
+}
+
+// a lock-free histogram of durations.
+pub(crate) struct Histogram {
+    buckets: [AtomicU64; BUCKETS],
+    sum_micros: AtomicU64,
+    max_micros: AtomicU64,
+}
+
+impl Histogram {
+    pub(crate) fn new() -> Self {
+        Self {
+            buckets: [const { AtomicU64::new(0) }; BUCKETS],
+            sum_micros: AtomicU64::new(0),
+            max_micros: AtomicU64::new(0),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn record(&self, elapsed: Duration) {
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        // Bucketed by the whole microseconds rounded up, so a bucket never takes a duration past its bound
+        let rounded_up = u64::try_from(elapsed.as_nanos().div_ceil(1000)).unwrap_or(u64::MAX);
+        self.buckets[Self::bucket(rounded_up)].fetch_add(1, Ordering::Relaxed);
+        self.sum_micros.fetch_add(micros, Ordering::Relaxed);
+        self.max_micros.fetch_max(micros, Ordering::Relaxed);
+    }
+
+    pub(crate) fn reset(&self) {
+        for bucket in &self.buckets {
+            bucket.store(0, Ordering::Relaxed);
+        }
+        self.sum_micros.store(0, Ordering::Relaxed);
+        self.max_micros.store(0, Ordering::Relaxed);
+    }
+
+    /// The number of durations in each bucket; bucket `i` holds those of at most `2^i` microseconds (see
+    /// [`bucket`](Self::bucket)).
+    pub(crate) fn counts(&self) -> [u64; BUCKETS] {
+        std::array::from_fn(|i| self.buckets[i].load(Ordering::Relaxed))
+    }
+
+    /// The total of all recorded durations.
+    pub(crate) fn sum(&self) -> Duration {
+        Duration::from_micros(self.sum_micros.load(Ordering::Relaxed))
+    }
+
+    pub(crate) fn summary(&self) -> DurationStats {
+        let counts = self.counts();
+        let count: u64 = counts.iter().sum();
+        let max = self.max_micros.load(Ordering::Relaxed);
+        let percentile = |pct: u64| {
+            if count == 0 {
+                return Duration::ZERO;
+            }
+            let rank = (count * pct).div_ceil(100);
+            let mut seen = 0;
+            for (i, n) in counts.iter().enumerate() {
+                seen += n;
+                if seen >= rank {
+                    return Duration::from_micros(Self::upper_bound(i).min(max));
+                }
+            }
+            Duration::from_micros(max)
+        };
+
+        DurationStats {
+            count,
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max: Duration::from_micros(max),
+        }
+    }
+
+    // bucket `i` holds `2^(i-1) + 1..=2^i` microseconds, and bucket 0 holds 0 and 1.
+    #[inline]
+    fn bucket(micros: u64) -> usize {
+        ((u64::BITS - micros.saturating_sub(1).leading_zeros()) as usize).min(BUCKETS - 1)
+    }
+
+    #[inline]
+    fn upper_bound(bucket: usize) -> u64 {
+        if bucket == BUCKETS - 1 {
+            u64::MAX
+        } else {
+            1 << bucket
+        }
+    }
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for Histogram {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        self.summary().fmt(f)
+    }
+}
+
+// the event counters behind `PoolStats`; the `live` gauge is kept in `PoolData` as it doubles as the cap.
+#[derive(Debug, Default)]
+pub(crate) struct PoolCounters {
+    pub(crate) checked_out: AtomicUsize,
+    pub(crate) created: AtomicU64,
+    pub(crate) discarded_full: AtomicU64,
+    pub(crate) discarded_reset: AtomicU64,
+    pub(crate) discarded_broken: AtomicU64,
+    pub(crate) discarded_panic: AtomicU64,
+    pub(crate) discarded_invalid: AtomicU64,
+    pub(crate) discarded_expired: AtomicU64,
+    pub(crate) discarded_max_uses: AtomicU64,
+    pub(crate) discarded_closed: AtomicU64,
+    pub(crate) detached: AtomicU64,
+    pub(crate) resets: AtomicU64,
+    pub(crate) hits: AtomicU64,
+    pub(crate) misses: AtomicU64,
+    pub(crate) high_water: AtomicUsize,
+    pub(crate) hold_time: Histogram,
+    pub(crate) wait_time: Histogram,
+}
+
+impl PoolCounters {
+    pub(crate) fn new(created: u64) -> Self {
+        Self {
+            created: AtomicU64::new(created),
+            ..Self::default()
+        }
+    }
+
+    #[inline]
+    pub(crate) fn check_out(&self) {
+        let checked_out = self.checked_out.fetch_add(1, Ordering::Relaxed) + 1;
+        self.high_water.fetch_max(checked_out, Ordering::Relaxed);
+    }
+
+    // `on_demand` for an object created to serve a checkout, which counts as a miss.
+    #[inline]
+    pub(crate) fn created(&self, on_demand: bool) {
+        self.created.fetch_add(1, Ordering::Relaxed);
+        if on_demand {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn discarded(&self, reason: DiscardReason) {
+        let counter = match reason {
+            DiscardReason::Full => &self.discarded_full,
+            DiscardReason::Reset => &self.discarded_reset,
+            DiscardReason::Broken => &self.discarded_broken,
+            DiscardReason::Panic => &self.discarded_panic,
+            DiscardReason::Invalid => &self.discarded_invalid,
+            DiscardReason::Expired => &self.discarded_expired,
+            DiscardReason::MaxUses => &self.discarded_max_uses,
+            DiscardReason::Closed => &self.discarded_closed,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn reset(&self) {
+        for counter in [
+            &self.created,
+            &self.discarded_full,
+            &self.discarded_reset,
+            &self.discarded_broken,
+            &self.discarded_panic,
+            &self.discarded_invalid,
+            &self.discarded_expired,
+            &self.discarded_max_uses,
+            &self.discarded_closed,
+            &self.detached,
+            &self.resets,
+            &self.hits,
+            &self.misses,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+        self.high_water
+            .store(self.checked_out.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.hold_time.reset();
+        self.wait_time.reset();
+    }
+
+    pub(crate) fn snapshot(&self, idle: usize, live: usize) -> PoolStats {
+        PoolStats {
+            idle,
+            live,
+            checked_out: self.checked_out.load(Ordering::Relaxed),
+            created: self.created.load(Ordering::Relaxed),
+            discarded_full: self.discarded_full.load(Ordering::Relaxed),
+            discarded_reset: self.discarded_reset.load(Ordering::Relaxed),
+            discarded_broken: self.discarded_broken.load(Ordering::Relaxed),
+            discarded_panic: self.discarded_panic.load(Ordering::Relaxed),
+            discarded_invalid: self.discarded_invalid.load(Ordering::Relaxed),
+            discarded_expired: self.discarded_expired.load(Ordering::Relaxed),
+            discarded_max_uses: self.discarded_max_uses.load(Ordering::Relaxed),
+            discarded_closed: self.discarded_closed.load(Ordering::Relaxed),
+            detached: self.detached.load(Ordering::Relaxed),
+            resets: self.resets.load(Ordering::Relaxed),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            high_water: self.high_water.load(Ordering::Relaxed),
+            hold_time: self.hold_time.summary(),
+            wait_time: self.wait_time.summary(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_empty() {
+        assert_eq!(Histogram::new().summary(), DurationStats::default());
+    }
+
+    #[test]
+    fn test_histogram_buckets() {
+        assert_eq!(Histogram::bucket(0), 0);
+        assert_eq!(Histogram::bucket(1), 0);
+        assert_eq!(Histogram::bucket(2), 1);
+        assert_eq!(Histogram::bucket(3), 2);
+        assert_eq!(Histogram::bucket(4), 2);
+        assert_eq!(Histogram::bucket(5), 3);
+        assert_eq!(Histogram::bucket(u64::MAX), BUCKETS - 1);
+        assert_eq!(Histogram::upper_bound(2), 4);
+    }
+
+    #[test]
+    fn test_histogram_power_of_two_boundary() {
+        let histogram = Histogram::new();
+        histogram.record(Duration::from_micros(4));
+        histogram.record(Duration::from_nanos(4_001));
+
+        let counts = histogram.counts();
+        assert_eq!(counts[2], 1); // Exactly 2^2 µs is in bucket 2
+        assert_eq!(counts[3], 1); // Anything longer is not, however small the excess
+    }
+
+    #[test]
+    fn test_histogram_percentiles() {
+        let histogram = Histogram::new();
+        for _ in 0..98 {
+            histogram.record(Duration::from_micros(3));
+        }
+        histogram.record(Duration::from_micros(100));
+        histogram.record(Duration::from_millis(5));
+
+        let summary = histogram.summary();
+        assert_eq!(summary.count, 100);
+        assert_eq!(summary.p50, Duration::from_micros(4)); // End of the 3..=4 bucket
+        assert_eq!(summary.p90, Duration::from_micros(4));
+        assert_eq!(summary.p99, Duration::from_micros(128)); // End of the 65..=128 bucket
+        assert_eq!(summary.max, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn test_histogram_percentile_capped_at_max() {
+        let histogram = Histogram::new();
+        histogram.record(Duration::from_micros(65));
+
+        let summary = histogram.summary();
+        assert_eq!(summary.p50, Duration::from_micros(65));
+        assert_eq!(summary.p99, Duration::from_micros(65));
+    }
+
+    #[test]
+    fn test_histogram_counts_and_sum() {
+        let histogram = Histogram::new();
+        histogram.record(Duration::from_micros(3));
+        histogram.record(Duration::from_micros(3));
+        histogram.record(Duration::from_millis(1));
+
+        let counts = histogram.counts();
+        assert_eq!(counts[2], 2);
+        assert_eq!(counts[10], 1); // 1000 µs is in 513..=1024
+        assert_eq!(counts.iter().sum::<u64>(), 3);
+        assert_eq!(histogram.sum(), Duration::from_micros(1006));
+    }
+
+    #[test]
+    fn test_histogram_reset() {
+        let histogram = Histogram::new();
+        histogram.record(Duration::from_secs(1));
+        histogram.reset();
+        assert_eq!(histogram.summary(), DurationStats::default());
+        assert_eq!(histogram.sum(), Duration::ZERO);
+    }
+}
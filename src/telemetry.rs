@@ -0,0 +1,394 @@
+use std::time::Duration;
+
+use crate::stats::DiscardReason;
+
+#[cfg(feature = "metrics")]
+use metrics::{Counter, Gauge, Histogram, counter, gauge, histogram};
+
+// metrics reported through the `metrics` facade and events emitted through `tracing`, both labelled with the pool name.
+// Without the `metrics` and `tracing` features this is empty and every method compiles to nothing, so the pool can
+// call them unconditionally.
+pub(crate) struct Telemetry {
+    #[cfg(feature = "tracing")]
+    pool: String,
+    #[cfg(feature = "metrics")]
+    idle: Gauge,
+    #[cfg(feature = "metrics")]
+    live: Gauge,
+    #[cfg(feature = "metrics")]
+    created: Counter,
+    // indexed by `DiscardReason`.
+    #[cfg(feature = "metrics")]
+    discarded: [Counter; DiscardReason::ALL.len()],
+    #[cfg(feature = "metrics")]
+    timeouts: Counter,
+    #[cfg(feature = "metrics")]
+    wait_seconds: Histogram,
+    #[cfg(feature = "metrics")]
+    hold_seconds: Histogram,
+}
+
+// the span of an object's reset, entered until dropped.
+pub(crate) struct ResetSpan {
+    #[cfg(feature = "tracing")]
+    _entered: tracing::span::EnteredSpan,
+}
+
+impl Telemetry {
+    // registers the pool's metrics once, so that reporting does not look them up again.
+    #[cfg_attr(
+        not(any(feature = "metrics", feature = "tracing")),
+        allow(unused_variables)
+    )]
+    pub(crate) fn new(name: &str, pre_created: usize) -> Self {
+        #[cfg(feature = "tracing")]
+        {
+            tracing::debug!(pool = %name, pre_created, "pool created");
+            // The pre-created objects take the first ids
+            for id in 0..pre_created as u64 {
+                tracing::debug!(pool = %name, id, "object created");
+            }
+        }
+        #[cfg(feature = "metrics")]
+        let labels = [("pool", name.to_string())];
+        let telemetry = Self {
+            #[cfg(feature = "tracing")]
+            pool: name.to_string(),
+            #[cfg(feature = "metrics")]
+            idle: gauge!("object_pool_idle", &labels),
+            #[cfg(feature = "metrics")]
+            live: gauge!("object_pool_live", &labels),
+            #[cfg(feature = "metrics")]
+            created: counter!("object_pool_created_total", &labels),
+            #[cfg(feature = "metrics")]
+            discarded: DiscardReason::ALL.map(|reason| {
+                let labels = [
+                    ("pool", name.to_string()),
+                    ("reason", reason.as_str().to_string()),
+                ];
+                counter!("object_pool_discarded_total", &labels)
+            }),
+            #[cfg(feature = "metrics")]
+            timeouts: counter!("object_pool_timeouts_total", &labels),
+            #[cfg(feature = "metrics")]
+            wait_seconds: histogram!("object_pool_wait_seconds", &labels),
+            #[cfg(feature = "metrics")]
+            hold_seconds: histogram!("object_pool_hold_seconds", &labels),
+        };
+        #[cfg(feature = "metrics")]
+        {
+            telemetry.created.increment(pre_created as u64);
+            telemetry.gauges(pre_created, pre_created);
+        }
+        telemetry
+    }
+
+    // only called with the feature on, so that the pool does not even read its gauges otherwise.
+    #[cfg(feature = "metrics")]
+    #[inline]
+    pub(crate) fn gauges(&self, idle: usize, live: usize) {
+        self.idle.set(idle as f64);
+        self.live.set(live as f64);
+    }
+
+    #[inline]
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn created(&self, id: u64) {
+        #[cfg(feature = "metrics")]
+        self.created.increment(1);
+        #[cfg(feature = "tracing")]
+        tracing::debug!(pool = %self.pool, id, "object created");
+    }
+
+    #[inline]
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn checked_out(&self, id: u64) {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(pool = %self.pool, id, "object checked out");
+    }
+
+    #[inline]
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn reset_span(&self, id: u64) -> ResetSpan {
+        ResetSpan {
+            #[cfg(feature = "tracing")]
+            _entered: tracing::trace_span!("reset", pool = %self.pool, id).entered(),
+        }
+    }
+
+    #[inline]
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn returned(&self, id: u64) {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(pool = %self.pool, id, "object returned");
+    }
+
+    #[inline]
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn discarded(&self, id: u64, reason: DiscardReason) {
+        #[cfg(feature = "metrics")]
+        self.discarded[reason as usize].increment(1);
+        #[cfg(feature = "tracing")]
+        tracing::debug!(pool = %self.pool, id, reason = %reason.as_str(), "object discarded");
+    }
+
+    #[inline]
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn detached(&self, id: u64) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(pool = %self.pool, id, "object detached");
+    }
+
+    #[inline]
+    #[cfg_attr(not(feature = "async"), allow(dead_code))]
+    pub(crate) fn timed_out(&self) {
+        #[cfg(feature = "metrics")]
+        self.timeouts.increment(1);
+        #[cfg(feature = "tracing")]
+        tracing::debug!(pool = %self.pool, "checkout timed out");
+    }
+
+    #[inline]
+    #[cfg_attr(not(feature = "async"), allow(dead_code))]
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+    pub(crate) fn waited(&self, elapsed: Duration) {
+        #[cfg(feature = "metrics")]
+        self.wait_seconds.record(elapsed.as_secs_f64());
+    }
+
+    #[inline]
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+    pub(crate) fn held(&self, elapsed: Duration) {
+        #[cfg(feature = "metrics")]
+        self.hold_seconds.record(elapsed.as_secs_f64());
+    }
+}
+
+// an object `on_destroy_async` could not hand to its hook, having been thrown away outside a tokio runtime. The hook
+// does not know which pool it belongs to, so the event has no pool name.
+#[cfg(feature = "rt")]
+#[inline]
+pub(crate) fn destroy_hook_skipped() {
+    #[cfg(feature = "tracing")]
+    tracing::warn!("object dropped without running on_destroy_async outside a tokio runtime");
+}
+
+#[cfg(all(test, feature = "metrics", feature = "async"))]
+mod tests {
+    use crate::{BundledPool, Resettable};
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    use metrics_util::{CompositeKey, MetricKind};
+    use std::time::Duration;
+
+    #[derive(Debug)]
+    struct TestObj;
+
+    impl Resettable for TestObj {
+        fn reset(&mut self) {}
+    }
+
+    fn find(
+        snapshot: &[(CompositeKey, DebugValue)],
+        kind: MetricKind,
+        name: &str,
+        labels: &[(&str, &str)],
+    ) -> DebugValue {
+        let (_, value) = snapshot
+            .iter()
+            .find(|(key, _)| {
+                key.kind() == kind
+                    && key.key().name() == name
+                    && key
+                        .key()
+                        .labels()
+                        .map(|label| (label.key(), label.value()))
+                        .eq(labels.iter().copied())
+            })
+            .unwrap_or_else(|| panic!("metric {} {:?} not recorded", name, labels));
+        match value {
+            DebugValue::Counter(n) => DebugValue::Counter(*n),
+            DebugValue::Gauge(v) => DebugValue::Gauge(*v),
+            DebugValue::Histogram(v) => DebugValue::Histogram(v.clone()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metrics_emitted_with_pool_name() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        // Handles are registered when the pool is built and keep reporting to this recorder afterwards.
+        let pool = metrics::with_local_recorder(&recorder, || {
+            BundledPool::builder()
+                .name("buffers")
+                .min_idle(1)
+                .max_idle(1)
+                .max_live(2)
+                .factory(|| TestObj)
+                .build()
+                .unwrap()
+        });
+
+        let item1 = pool.take().unwrap();
+        let item2 = pool.take_async().await.unwrap();
+        assert!(pool.take_timeout(Duration::from_millis(1)).await.is_err());
+        drop(item1);
+        drop(item2); // Discarded, the pool already holds `max_idle` objects
+
+        let snapshot: Vec<_> = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, _, _, value)| (key, value))
+            .collect();
+        let pool = [("pool", "buffers")];
+        let counter = |name| find(&snapshot, MetricKind::Counter, name, &pool);
+        let gauge = |name| find(&snapshot, MetricKind::Gauge, name, &pool);
+        let discarded = |reason| {
+            let labels = [("pool", "buffers"), ("reason", reason)];
+            find(
+                &snapshot,
+                MetricKind::Counter,
+                "object_pool_discarded_total",
+                &labels,
+            )
+        };
+        let histogram_len = |name| match find(&snapshot, MetricKind::Histogram, name, &pool) {
+            DebugValue::Histogram(values) => values.len(),
+            _ => unreachable!(),
+        };
+
+        assert_eq!(counter("object_pool_created_total"), DebugValue::Counter(2));
+        assert_eq!(discarded("full"), DebugValue::Counter(1));
+        assert_eq!(discarded("reset"), DebugValue::Counter(0));
+        assert_eq!(
+            counter("object_pool_timeouts_total"),
+            DebugValue::Counter(1)
+        );
+        assert_eq!(gauge("object_pool_idle"), DebugValue::Gauge(1.0.into()));
+        assert_eq!(gauge("object_pool_live"), DebugValue::Gauge(1.0.into()));
+        assert_eq!(histogram_len("object_pool_wait_seconds"), 1);
+        assert_eq!(histogram_len("object_pool_hold_seconds"), 2);
+    }
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tracing_tests {
+    use crate::{BundledPool, Resettable};
+    use std::fmt::{Debug, Write};
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    #[derive(Debug)]
+    struct TestObj;
+
+    impl Resettable for TestObj {
+        fn reset(&mut self) {}
+    }
+
+    // records every span and event as one line of `name key=value ...`.
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    struct Line(String);
+
+    impl Visit for Line {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            if field.name() == "message" {
+                write!(self.0, "{:?}", value).unwrap();
+            } else {
+                write!(self.0, " {}={:?}", field.name(), value).unwrap();
+            }
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut line = Line(format!("span {}", span.metadata().name()));
+            span.record(&mut line);
+            self.0.lock().unwrap().push(line.0);
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut line = Line(String::new());
+            event.record(&mut line);
+            self.0.lock().unwrap().push(line.0);
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn test_lifecycle_events() {
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let pool = BundledPool::builder()
+                .name("buffers")
+                .min_idle(1)
+                .max_idle(1)
+                .factory(|| TestObj)
+                .build()
+                .unwrap();
+            let item1 = pool.take().unwrap();
+            let item2 = pool.take().unwrap();
+            assert_eq!((item1.id(), item2.id()), (0, 1));
+            drop(item1);
+            drop(item2);
+            pool.take().unwrap().detach();
+        });
+
+        let lines = recorder.0.lock().unwrap();
+        assert_eq!(
+            *lines,
+            [
+                "pool created pool=buffers pre_created=1",
+                "object created pool=buffers id=0",
+                "object checked out pool=buffers id=0",
+                "object created pool=buffers id=1",
+                "object checked out pool=buffers id=1",
+                "span reset pool=buffers id=0",
+                "object returned pool=buffers id=0",
+                "span reset pool=buffers id=1",
+                "object returned pool=buffers id=1",
+                "object discarded pool=buffers id=1 reason=full",
+                "object checked out pool=buffers id=0",
+                "object detached pool=buffers id=0",
+            ]
+        );
+    }
+
+    #[cfg(feature = "rt")]
+    #[test]
+    fn test_destroy_hook_skipped_outside_runtime() {
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let pool = BundledPool::builder()
+                .max_idle(1)
+                .factory(|| TestObj)
+                .on_destroy_async(|_| async {})
+                .build()
+                .unwrap();
+            let item1 = pool.take().unwrap();
+            let item2 = pool.take().unwrap();
+            drop(item1);
+            drop(item2);
+        });
+
+        let lines = recorder.0.lock().unwrap();
+        assert!(lines.contains(
+            &"object dropped without running on_destroy_async outside a tokio runtime".to_string()
+        ));
+    }
+}